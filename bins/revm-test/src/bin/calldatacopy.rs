@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use revm::{
+    db::InMemoryDB,
+    primitives::{AccountInfo, Bytecode, TransactTo, B160, U256},
+};
+use std::time::Duration;
+extern crate alloc;
+
+fn main() {
+    let target = B160::from(0x1000);
+
+    // Copies all calldata to fresh memory.
+    // CALLDATASIZE PUSH1 0 PUSH1 0 CALLDATACOPY STOP
+    let code = hex::decode("36600060003700").unwrap();
+
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        target,
+        AccountInfo::new(U256::ZERO, 1, Bytecode::new_raw(Bytes::from(code))),
+    );
+
+    let mut evm = revm::new();
+    evm.database(db);
+    evm.env.tx.transact_to = TransactTo::Call(target);
+    evm.env.tx.data = Bytes::from(vec![0xab; 128 * 1024]);
+    evm.env.tx.gas_limit = 10_000_000;
+
+    // Microbenchmark
+    let bench_options = microbench::Options::default().time(Duration::from_secs(2));
+
+    microbench::bench(&bench_options, "CALLDATACOPY 128KB", || {
+        let _ = evm.transact().unwrap();
+    });
+}
@@ -0,0 +1,46 @@
+use bytes::Bytes;
+use revm::{
+    db::InMemoryDB,
+    primitives::{AccountInfo, Bytecode, TransactTo, B160, U256},
+};
+use std::time::Duration;
+extern crate alloc;
+
+fn main() {
+    let target = B160::from(0x1000);
+    let other = B160::from(0x2000);
+
+    // Loop that copies 24KB of code of `other` to memory 100 times.
+    // PUSH1 100 JUMPDEST PUSH2 0x6000 PUSH1 0 PUSH1 0 PUSH20 other EXTCODECOPY
+    // PUSH1 1 SWAP1 SUB DUP1 PUSH1 2 JUMPI STOP
+    let code = hex::decode(
+        "60645b616000600060007300000000000000000000000000000000000020003c600190038060025700",
+    )
+    .unwrap();
+
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        target,
+        AccountInfo::new(U256::ZERO, 1, Bytecode::new_raw(Bytes::from(code))),
+    );
+    db.insert_account_info(
+        other,
+        AccountInfo::new(
+            U256::ZERO,
+            1,
+            Bytecode::new_raw(Bytes::from(vec![0x5b; 0x6000])).to_checked(),
+        ),
+    );
+
+    let mut evm = revm::new();
+    evm.database(db);
+    evm.env.tx.transact_to = TransactTo::Call(target);
+    evm.env.tx.gas_limit = 10_000_000;
+
+    // Microbenchmark
+    let bench_options = microbench::Options::default().time(Duration::from_secs(2));
+
+    microbench::bench(&bench_options, "EXTCODECOPY 24KB", || {
+        let _ = evm.transact().unwrap();
+    });
+}
@@ -0,0 +1,41 @@
+use bytes::Bytes;
+use revm::{
+    db::BenchmarkDB,
+    primitives::{Bytecode, GasSchedule, SpecId, TransactTo},
+};
+use std::time::Duration;
+extern crate alloc;
+
+fn main() {
+    // Loop that runs 1000 times and does SLOAD, SSTORE and MSTORE in every iteration.
+    // PUSH2 0x03e8 JUMPDEST DUP1 SLOAD POP DUP1 DUP1 SSTORE DUP1 PUSH1 0 MSTORE
+    // PUSH1 1 SWAP1 SUB DUP1 PUSH1 3 JUMPI STOP
+    let code = hex::decode("6103e85b80545080805580600052600190038060035700").unwrap();
+
+    let mut evm = revm::new();
+    evm.env.tx.caller = "0x0000000000000000000000000000000000000001"
+        .parse()
+        .unwrap();
+    evm.env.tx.transact_to = TransactTo::Call(
+        "0x0000000000000000000000000000000000000000"
+            .parse()
+            .unwrap(),
+    );
+    evm.database(BenchmarkDB::new_bytecode(Bytecode::new_raw(Bytes::from(
+        code,
+    ))));
+
+    // Microbenchmark
+    let bench_options = microbench::Options::default().time(Duration::from_secs(2));
+
+    microbench::bench(&bench_options, "Spec gas schedule", || {
+        let _ = evm.transact().unwrap();
+    });
+
+    // Same costs as the spec, but read from the schedule at runtime.
+    evm.env.cfg.gas_schedule = Some(GasSchedule::new(SpecId::LATEST));
+
+    microbench::bench(&bench_options, "Custom gas schedule", || {
+        let _ = evm.transact().unwrap();
+    });
+}
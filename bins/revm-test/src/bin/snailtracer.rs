@@ -3,6 +3,7 @@ use std::time::Duration;
 use bytes::Bytes;
 use revm::{
     db::BenchmarkDB,
+    inspectors::{JumpTraceInspector, NoOpInspector},
     interpreter::{analysis::to_analysed, BytecodeLocked, Contract, DummyHost, Interpreter},
     primitives::{BerlinSpec, Bytecode, TransactTo},
 };
@@ -39,6 +40,22 @@ pub fn simple_example() {
         },
     );
 
+    microbench::bench(
+        &bench_options,
+        "Snailtracer NoOpInspector benchmark",
+        || {
+            let _ = evm.inspect(NoOpInspector()).unwrap();
+        },
+    );
+
+    microbench::bench(
+        &bench_options,
+        "Snailtracer JumpTraceInspector benchmark",
+        || {
+            let _ = evm.inspect(JumpTraceInspector::default()).unwrap();
+        },
+    );
+
     // revm interpreter
     let contract = Contract {
         input: evm.env.tx.data,
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+use revm::{
+    db::InMemoryDB,
+    primitives::{AccountInfo, Bytecode, TransactTo, B160, U256},
+};
+use std::time::Duration;
+extern crate alloc;
+
+fn main() {
+    let target = B160::from(0x1000);
+
+    // Writes 10000 different values to storage slot 0.
+    // PUSH2 10000 JUMPDEST DUP1 PUSH1 0 SSTORE PUSH1 1 SWAP1 SUB DUP1 PUSH1 3 JUMPI STOP
+    let code = hex::decode("6127105b80600055600190038060035700").unwrap();
+
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        target,
+        AccountInfo::new(U256::ZERO, 1, Bytecode::new_raw(Bytes::from(code))),
+    );
+
+    let mut evm = revm::new();
+    evm.database(db);
+    evm.env.tx.transact_to = TransactTo::Call(target);
+    evm.env.tx.gas_limit = 10_000_000;
+
+    // Microbenchmark
+    let bench_options = microbench::Options::default().time(Duration::from_secs(2));
+
+    microbench::bench(&bench_options, "SSTORE 10k writes to one slot", || {
+        let _ = evm.transact().unwrap();
+    });
+}
@@ -15,6 +15,11 @@ pub struct Gas {
     /// Used gas for memory expansion
     memory: u64,
     /// Refunded gas. This gas is used only at the end of execution.
+    ///
+    /// Every call frame has its own counter. Refund of the child frame is added to the parent
+    /// only if the child returned successfully, so refunds of reverted or failed frames are
+    /// discarded together with their state changes. EIP-3529 cap is applied once, when
+    /// transaction is finalized.
     refunded: i64,
 }
 impl Gas {
@@ -3,19 +3,24 @@ use crate::alloc::vec::Vec;
 use crate::{
     inner_models::SelfDestructResult,
     primitives::Spec,
-    primitives::{SpecId::*, U256},
+    primitives::{GasSchedule, SpecId::*, U256},
 };
 use revm_primitives::{Bytes, B160};
 
-#[allow(clippy::collapsible_else_if)]
 pub fn sstore_refund<SPEC: Spec>(original: U256, current: U256, new: U256) -> i64 {
+    sstore_refund_with::<SPEC>(&SPEC::GAS_SCHEDULE, original, current, new)
+}
+
+/// Same as [sstore_refund] but with costs taken from the given gas schedule.
+#[allow(clippy::collapsible_else_if)]
+pub fn sstore_refund_with<SPEC: Spec>(
+    schedule: &GasSchedule,
+    original: U256,
+    current: U256,
+    new: U256,
+) -> i64 {
+    let sstore_clears_schedule = schedule.sstore_clears_refund as i64;
     if SPEC::enabled(ISTANBUL) {
-        // EIP-3529: Reduction in refunds
-        let sstore_clears_schedule = if SPEC::enabled(LONDON) {
-            (SSTORE_RESET - COLD_SLOAD_COST + ACCESS_LIST_STORAGE_KEY) as i64
-        } else {
-            REFUND_SSTORE_CLEARS
-        };
         if current == new {
             0
         } else {
@@ -34,14 +39,19 @@ pub fn sstore_refund<SPEC: Spec>(original: U256, current: U256, new: U256) -> i6
 
                 if original == new {
                     let (gas_sstore_reset, gas_sload) = if SPEC::enabled(BERLIN) {
-                        (SSTORE_RESET - COLD_SLOAD_COST, WARM_STORAGE_READ_COST)
+                        (
+                            schedule
+                                .sstore_reset
+                                .saturating_sub(schedule.cold_sload_cost),
+                            schedule.warm_storage_read_cost,
+                        )
                     } else {
-                        (SSTORE_RESET, sload_cost::<SPEC>(false))
+                        (schedule.sstore_reset, sload_cost_with(schedule, false))
                     };
                     if original == U256::ZERO {
-                        refund += (SSTORE_SET - gas_sload) as i64;
+                        refund += schedule.sstore_set.saturating_sub(gas_sload) as i64;
                     } else {
-                        refund += (gas_sstore_reset - gas_sload) as i64;
+                        refund += gas_sstore_reset.saturating_sub(gas_sload) as i64;
                     }
                 }
 
@@ -50,7 +60,7 @@ pub fn sstore_refund<SPEC: Spec>(original: U256, current: U256, new: U256) -> i6
         }
     } else {
         if current != U256::ZERO && new == U256::ZERO {
-            REFUND_SSTORE_CLEARS
+            sstore_clears_schedule
         } else {
             0
         }
@@ -162,43 +172,57 @@ pub fn initcode_cost(len: u64) -> u64 {
 }
 
 pub fn sload_cost<SPEC: Spec>(is_cold: bool) -> u64 {
-    if SPEC::enabled(BERLIN) {
-        if is_cold {
-            COLD_SLOAD_COST
-        } else {
-            WARM_STORAGE_READ_COST
-        }
-    } else if SPEC::enabled(ISTANBUL) {
-        // EIP-1884: Repricing for trie-size-dependent opcodes
-        800
-    } else if SPEC::enabled(TANGERINE) {
-        // EIP-150: Gas cost changes for IO-heavy operations
-        200
+    sload_cost_with(&SPEC::GAS_SCHEDULE, is_cold)
+}
+
+/// Same as [sload_cost] but with costs taken from the given gas schedule.
+///
+/// Before Berlin cold and warm costs of the spec schedule are the same.
+pub fn sload_cost_with(schedule: &GasSchedule, is_cold: bool) -> u64 {
+    if is_cold {
+        schedule.cold_sload_cost
     } else {
-        50
+        schedule.warm_storage_read_cost
     }
 }
 
-#[allow(clippy::collapsible_else_if)]
 pub fn sstore_cost<SPEC: Spec>(
     original: U256,
     current: U256,
     new: U256,
     gas: u64,
     is_cold: bool,
+) -> Option<u64> {
+    sstore_cost_with::<SPEC>(&SPEC::GAS_SCHEDULE, original, current, new, gas, is_cold)
+}
+
+/// Same as [sstore_cost] but with costs taken from the given gas schedule.
+#[allow(clippy::collapsible_else_if)]
+pub fn sstore_cost_with<SPEC: Spec>(
+    schedule: &GasSchedule,
+    original: U256,
+    current: U256,
+    new: U256,
+    gas: u64,
+    is_cold: bool,
 ) -> Option<u64> {
     // TODO untangle this mess and make it more elegant
     let (gas_sload, gas_sstore_reset) = if SPEC::enabled(BERLIN) {
-        (WARM_STORAGE_READ_COST, SSTORE_RESET - COLD_SLOAD_COST)
+        (
+            schedule.warm_storage_read_cost,
+            schedule
+                .sstore_reset
+                .saturating_sub(schedule.cold_sload_cost),
+        )
     } else {
-        (sload_cost::<SPEC>(is_cold), SSTORE_RESET)
+        (sload_cost_with(schedule, is_cold), schedule.sstore_reset)
     };
 
     // https://eips.ethereum.org/EIPS/eip-2200
     // It’s a combined version of EIP-1283 and EIP-1706
     let gas_cost = if SPEC::enabled(ISTANBUL) {
         // EIP-1706
-        if gas <= CALL_STIPEND {
+        if gas <= schedule.call_stipend {
             return None;
         }
 
@@ -208,7 +232,7 @@ pub fn sstore_cost<SPEC: Spec>(
         } else {
             if original == current {
                 if original == U256::ZERO {
-                    SSTORE_SET
+                    schedule.sstore_set
                 } else {
                     gas_sstore_reset
                 }
@@ -218,14 +242,14 @@ pub fn sstore_cost<SPEC: Spec>(
         }
     } else {
         if current == U256::ZERO && new != U256::ZERO {
-            SSTORE_SET
+            schedule.sstore_set
         } else {
             gas_sstore_reset
         }
     };
     // In EIP-2929 we charge extra if the slot has not been used yet in this transaction
     if SPEC::enabled(BERLIN) && is_cold {
-        Some(gas_cost + COLD_SLOAD_COST)
+        Some(gas_cost + schedule.cold_sload_cost)
     } else {
         Some(gas_cost)
     }
@@ -302,23 +326,19 @@ fn xfer_cost(is_call_or_callcode: bool, transfers_value: bool) -> u64 {
     }
 }
 
+/// Cost of creating the called account.
+///
+/// `is_new` has to be sampled before the call transfers value to the account. Before EIP-161 it
+/// is true for accounts that do not exist, after it for empty accounts.
 fn new_cost<SPEC: Spec>(is_call_or_staticcall: bool, is_new: bool, transfers_value: bool) -> u64 {
-    if is_call_or_staticcall {
-        // EIP-161: State trie clearing (invariant-preserving alternative)
-        if SPEC::enabled(SPURIOUS_DRAGON) {
-            if transfers_value && is_new {
-                NEWACCOUNT
-            } else {
-                0
-            }
-        } else if is_new {
-            NEWACCOUNT
-        } else {
-            0
-        }
-    } else {
-        0
+    if !is_call_or_staticcall || !is_new {
+        return 0;
+    }
+    // EIP-161: State trie clearing (invariant-preserving alternative)
+    if SPEC::enabled(SPURIOUS_DRAGON) && !transfers_value {
+        return 0;
     }
+    NEWACCOUNT
 }
 
 pub fn memory_gas(a: usize) -> u64 {
@@ -328,21 +348,40 @@ pub fn memory_gas(a: usize) -> u64 {
         .saturating_add(a.saturating_mul(a) / 512)
 }
 
+/// Same as [memory_gas] but with linear cost taken from the given gas schedule.
+pub fn memory_gas_with(schedule: &GasSchedule, a: usize) -> u64 {
+    let a = a as u64;
+    schedule
+        .memory_word
+        .saturating_mul(a)
+        .saturating_add(a.saturating_mul(a) / 512)
+}
+
 /// Initial gas that is deducted for transaction to be included.
 /// Initial gas contains initial stipend gas, gas for access list and input data.
 pub fn initial_tx_gas<SPEC: Spec>(
     input: &Bytes,
     is_create: bool,
     access_list: &[(B160, Vec<U256>)],
+) -> u64 {
+    initial_tx_gas_with::<SPEC>(&SPEC::GAS_SCHEDULE, input, is_create, access_list)
+}
+
+/// Same as [initial_tx_gas] but with input data costs taken from the given gas schedule.
+pub fn initial_tx_gas_with<SPEC: Spec>(
+    schedule: &GasSchedule,
+    input: &Bytes,
+    is_create: bool,
+    access_list: &[(B160, Vec<U256>)],
 ) -> u64 {
     let mut initial_gas = 0;
     let zero_data_len = input.iter().filter(|v| **v == 0).count() as u64;
     let non_zero_data_len = input.len() as u64 - zero_data_len;
 
     // initdate stipend
-    initial_gas += zero_data_len * TRANSACTION_ZERO_DATA;
+    initial_gas += zero_data_len * schedule.calldata_zero_byte;
     // EIP-2028: Transaction data gas cost reduction
-    initial_gas += non_zero_data_len * if SPEC::enabled(ISTANBUL) { 16 } else { 68 };
+    initial_gas += non_zero_data_len * schedule.calldata_nonzero_byte;
 
     // get number of access list account and storages.
     if SPEC::enabled(BERLIN) {
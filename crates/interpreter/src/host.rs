@@ -2,7 +2,7 @@ mod dummy_host;
 
 use crate::primitives::Bytecode;
 use crate::{
-    primitives::{Bytes, Env, B160, B256, U256},
+    primitives::{Bytes, ColdAccess, Env, B160, B256, U256},
     CallInputs, CreateInputs, Gas, InstructionResult, Interpreter, SelfDestructResult,
 };
 pub use alloc::vec::Vec;
@@ -19,6 +19,13 @@ pub trait Host {
 
     fn env(&mut self) -> &mut Env;
 
+    /// Consume one instruction from the instruction limit of the transaction.
+    /// Returns false if the limit is exhausted and instruction should not be executed.
+    #[cfg(feature = "optional_instruction_limit")]
+    fn consume_instruction(&mut self) -> bool {
+        true
+    }
+
     /// load account. Returns (is_cold,is_new_account)
     fn load_account(&mut self, address: B160) -> Option<(bool, bool)>;
     /// Get environmental block hash.
@@ -26,6 +33,9 @@ pub trait Host {
     /// Get balance of address and if account is cold loaded.
     fn balance(&mut self, address: B160) -> Option<(U256, bool)>;
     /// Get code of address and if account is cold loaded.
+    ///
+    /// Bytecode is reference counted, implementations should return a clone of the loaded code
+    /// so that instructions can read it without copying.
     fn code(&mut self, address: B160) -> Option<(Bytecode, bool)>;
     /// Get code hash of address and if account is cold loaded.
     fn code_hash(&mut self, address: B160) -> Option<(B256, bool)>;
@@ -50,4 +60,6 @@ pub trait Host {
     ) -> (InstructionResult, Option<B160>, Gas, Bytes);
     /// Invoke a call operation.
     fn call(&mut self, input: &mut CallInputs) -> (InstructionResult, Gas, Bytes);
+    /// Called after the cold access was charged. Default implementation does nothing.
+    fn record_cold_access(&mut self, _access: ColdAccess) {}
 }
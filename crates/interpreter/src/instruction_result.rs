@@ -40,6 +40,8 @@ pub enum InstructionResult {
     CreateContractStartingWithEF,
     /// EIP-3860: Limit and meter initcode. Initcode size limit exceeded.
     CreateInitcodeSizeLimit,
+    /// Instruction limit of the transaction is exhausted. Treated as out of gas.
+    InstructionLimitReached,
 
     // Fatal external error. Returned by database.
     FatalExternalError,
@@ -70,6 +72,7 @@ impl InstructionResult {
                 | Self::CreateContractSizeLimit
                 | Self::CreateContractStartingWithEF
                 | Self::CreateInitcodeSizeLimit
+                | Self::InstructionLimitReached
                 | Self::FatalExternalError
         )
     }
@@ -165,6 +168,7 @@ impl From<InstructionResult> for SuccessOrHalt {
                 Self::Halt(Halt::CreateContractSizeLimit)
             }
             InstructionResult::CreateInitcodeSizeLimit => Self::Halt(Halt::CreateInitcodeSizeLimit),
+            InstructionResult::InstructionLimitReached => Self::Halt(Halt::InstructionLimitReached),
             InstructionResult::FatalExternalError => Self::FatalExternalError,
         }
     }
@@ -1,15 +1,43 @@
-use crate::primitives::{Bytes, Spec, SpecId::*, B160, B256, U256};
-use crate::MAX_INITCODE_SIZE;
+use super::opcode;
+use crate::primitives::{Bytes, ColdAccess, Spec, SpecId::*, B160, B256, BLOCK_HASH_HISTORY, U256};
 use crate::{
     alloc::boxed::Box,
     alloc::vec::Vec,
-    gas::{self, COLD_ACCOUNT_ACCESS_COST, WARM_STORAGE_READ_COST},
+    gas::{self, COLD_ACCOUNT_ACCESS_COST, COLD_SLOAD_COST, WARM_STORAGE_READ_COST},
     interpreter::Interpreter,
     return_ok, return_revert, CallContext, CallInputs, CallScheme, CreateInputs, CreateScheme,
     Host, InstructionResult, Transfer,
 };
 use core::cmp::min;
 
+/// Reports charged EIP-2929 cold access to the host.
+///
+/// Storage access cost is taken from the gas schedule of the interpreter if it is set.
+#[inline(always)]
+fn record_cold_access<SPEC: Spec>(
+    interpreter: &Interpreter,
+    host: &mut dyn Host,
+    is_cold: bool,
+    address: B160,
+    slot: Option<U256>,
+    opcode: u8,
+) {
+    if SPEC::enabled(BERLIN) && is_cold {
+        host.record_cold_access(ColdAccess {
+            address,
+            slot,
+            opcode,
+            gas_charged: if slot.is_some() {
+                interpreter
+                    .gas_schedule
+                    .map_or(COLD_SLOAD_COST, |schedule| schedule.cold_sload_cost)
+            } else {
+                COLD_ACCOUNT_ACCESS_COST
+            },
+        });
+    }
+}
+
 pub fn balance<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Host) {
     pop_address!(interpreter, address);
     let ret = host.balance(address);
@@ -29,6 +57,7 @@ pub fn balance<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Host) {
             20
         }
     );
+    record_cold_access::<SPEC>(interpreter, host, is_cold, address, None, opcode::BALANCE);
     push!(interpreter, balance);
 }
 
@@ -67,6 +96,14 @@ pub fn extcodesize<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Hos
     } else {
         gas!(interpreter, 20);
     }
+    record_cold_access::<SPEC>(
+        interpreter,
+        host,
+        is_cold,
+        address,
+        None,
+        opcode::EXTCODESIZE,
+    );
 
     push!(interpreter, U256::from(code.len()));
 }
@@ -94,6 +131,14 @@ pub fn extcodehash<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Hos
     } else {
         gas!(interpreter, 400);
     }
+    record_cold_access::<SPEC>(
+        interpreter,
+        host,
+        is_cold,
+        address,
+        None,
+        opcode::EXTCODEHASH,
+    );
     push_b256!(interpreter, code_hash);
 }
 
@@ -113,6 +158,14 @@ pub fn extcodecopy<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Hos
         interpreter,
         gas::extcodecopy_cost::<SPEC>(len as u64, is_cold)
     );
+    record_cold_access::<SPEC>(
+        interpreter,
+        host,
+        is_cold,
+        address,
+        None,
+        opcode::EXTCODECOPY,
+    );
     if len == 0 {
         return;
     }
@@ -121,13 +174,13 @@ pub fn extcodecopy<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Hos
         memory_offset,
         InstructionResult::InvalidOperandOOG
     );
+    // slice of the shared buffer without padding, part of the window past it is zeroed.
+    let code = code.original_bytes();
     let code_offset = min(as_usize_saturated!(code_offset), code.len());
-    memory_resize!(interpreter, memory_offset, len);
-
-    // Safety: set_data is unsafe function and memory_resize ensures us that it is safe to call it
+    let new_size = memory_expansion!(interpreter, memory_offset, len);
     interpreter
         .memory
-        .set_data(memory_offset, code_offset, len, code.bytes());
+        .resize_and_set_data(new_size, memory_offset, code_offset, len, &code);
 }
 
 pub fn blockhash(interpreter: &mut Interpreter, host: &mut dyn Host) {
@@ -137,7 +190,7 @@ pub fn blockhash(interpreter: &mut Interpreter, host: &mut dyn Host) {
     if let Some(diff) = host.env().block.number.checked_sub(*number) {
         let diff = as_usize_saturated!(diff);
         // blockhash should push zero if number is same as current block number.
-        if diff <= 256 && diff != 0 {
+        if diff <= BLOCK_HASH_HISTORY && diff != 0 {
             let ret = host.block_hash(*number);
             if ret.is_none() {
                 interpreter.instruction_result = InstructionResult::FatalExternalError;
@@ -159,7 +212,19 @@ pub fn sload<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Host) {
         return;
     }
     let (value, is_cold) = ret.unwrap();
-    gas!(interpreter, gas::sload_cost::<SPEC>(is_cold));
+    let cost = match &interpreter.gas_schedule {
+        Some(schedule) => gas::sload_cost_with(schedule, is_cold),
+        None => gas::sload_cost::<SPEC>(is_cold),
+    };
+    gas!(interpreter, cost);
+    record_cold_access::<SPEC>(
+        interpreter,
+        host,
+        is_cold,
+        interpreter.contract.address,
+        Some(index),
+        opcode::SLOAD,
+    );
     push!(interpreter, value);
 }
 
@@ -175,9 +240,26 @@ pub fn sstore<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Host) {
     let (original, old, new, is_cold) = ret.unwrap();
     gas_or_fail!(interpreter, {
         let remaining_gas = interpreter.gas.remaining();
-        gas::sstore_cost::<SPEC>(original, old, new, remaining_gas, is_cold)
+        match &interpreter.gas_schedule {
+            Some(schedule) => {
+                gas::sstore_cost_with::<SPEC>(schedule, original, old, new, remaining_gas, is_cold)
+            }
+            None => gas::sstore_cost::<SPEC>(original, old, new, remaining_gas, is_cold),
+        }
     });
-    refund!(interpreter, gas::sstore_refund::<SPEC>(original, old, new));
+    record_cold_access::<SPEC>(
+        interpreter,
+        host,
+        is_cold,
+        interpreter.contract.address,
+        Some(index),
+        opcode::SSTORE,
+    );
+    let refund = match &interpreter.gas_schedule {
+        Some(schedule) => gas::sstore_refund_with::<SPEC>(schedule, original, old, new),
+        None => gas::sstore_refund::<SPEC>(original, old, new),
+    };
+    refund!(interpreter, refund);
 }
 
 pub fn log<const N: u8>(interpreter: &mut Interpreter, host: &mut dyn Host) {
@@ -226,12 +308,21 @@ pub fn selfdestruct<SPEC: Spec>(interpreter: &mut Interpreter, host: &mut dyn Ho
         refund!(interpreter, gas::SELFDESTRUCT)
     }
     gas!(interpreter, gas::selfdestruct_cost::<SPEC>(res));
+    record_cold_access::<SPEC>(
+        interpreter,
+        host,
+        res.is_cold,
+        target,
+        None,
+        opcode::SELFDESTRUCT,
+    );
 
     interpreter.instruction_result = InstructionResult::SelfDestruct;
 }
 
 pub fn prepare_create_inputs<const IS_CREATE2: bool, SPEC: Spec>(
     interpreter: &mut Interpreter,
+    host: &mut dyn Host,
     create_inputs: &mut Option<Box<CreateInputs>>,
 ) {
     check_staticcall!(interpreter);
@@ -255,7 +346,7 @@ pub fn prepare_create_inputs<const IS_CREATE2: bool, SPEC: Spec>(
         );
         // EIP-3860: Limit and meter initcode
         if SPEC::enabled(SHANGHAI) {
-            if len > MAX_INITCODE_SIZE {
+            if len > host.env().cfg.max_initcode_size() {
                 interpreter.instruction_result = InstructionResult::CreateInitcodeSizeLimit;
                 return;
             }
@@ -297,7 +388,7 @@ pub fn create<const IS_CREATE2: bool, SPEC: Spec>(
     host: &mut dyn Host,
 ) {
     let mut create_input: Option<Box<CreateInputs>> = None;
-    prepare_create_inputs::<IS_CREATE2, SPEC>(interpreter, &mut create_input);
+    prepare_create_inputs::<IS_CREATE2, SPEC>(interpreter, host, &mut create_input);
 
     let Some(mut create_input) = create_input else {
         return;
@@ -468,6 +559,13 @@ fn prepare_call_inputs<SPEC: Spec>(
             matches!(scheme, CallScheme::Call | CallScheme::StaticCall),
         )
     );
+    let call_opcode = match scheme {
+        CallScheme::Call => opcode::CALL,
+        CallScheme::CallCode => opcode::CALLCODE,
+        CallScheme::DelegateCall => opcode::DELEGATECALL,
+        CallScheme::StaticCall => opcode::STATICCALL,
+    };
+    record_cold_access::<SPEC>(interpreter, host, is_cold, to, None, call_opcode);
 
     // take l64 part of gas_limit
     let mut gas_limit = if SPEC::enabled(TANGERINE) {
@@ -482,7 +580,10 @@ fn prepare_call_inputs<SPEC: Spec>(
 
     // add call stipend if there is value to be transferred.
     if matches!(scheme, CallScheme::Call | CallScheme::CallCode) && transfer.value != U256::ZERO {
-        gas_limit = gas_limit.saturating_add(gas::CALL_STIPEND);
+        let call_stipend = interpreter
+            .gas_schedule
+            .map_or(gas::CALL_STIPEND, |schedule| schedule.call_stipend);
+        gas_limit = gas_limit.saturating_add(call_stipend);
     }
     let is_static = matches!(scheme, CallScheme::StaticCall) || interpreter.is_static;
 
@@ -51,7 +51,10 @@ macro_rules! gas_or_fail {
     };
 }
 
-macro_rules! memory_resize {
+/// Checks the memory limit and charges the expansion gas of the `offset..offset + len` range.
+///
+/// Evaluates to the memory size needed by the range, the memory is not resized.
+macro_rules! memory_expansion {
     ($interp:expr, $offset:expr, $len:expr) => {{
         let len: usize = $len;
         let offset: usize = $offset;
@@ -64,16 +67,18 @@ macro_rules! memory_resize {
                 return;
             }
 
-            if new_size > $interp.memory.len() {
-                if crate::USE_GAS {
-                    let num_bytes = new_size / 32;
-                    if !$interp.gas.record_memory(crate::gas::memory_gas(num_bytes)) {
-                        $interp.instruction_result = InstructionResult::MemoryLimitOOG;
-                        return;
-                    }
+            if crate::USE_GAS && new_size > $interp.memory.len() {
+                let num_bytes = new_size / 32;
+                let memory_gas = match &$interp.gas_schedule {
+                    Some(schedule) => crate::gas::memory_gas_with(schedule, num_bytes),
+                    None => crate::gas::memory_gas(num_bytes),
+                };
+                if !$interp.gas.record_memory(memory_gas) {
+                    $interp.instruction_result = InstructionResult::MemoryLimitOOG;
+                    return;
                 }
-                $interp.memory.resize(new_size);
             }
+            new_size
         } else {
             $interp.instruction_result = InstructionResult::MemoryOOG;
             return;
@@ -81,6 +86,15 @@ macro_rules! memory_resize {
     }};
 }
 
+macro_rules! memory_resize {
+    ($interp:expr, $offset:expr, $len:expr) => {{
+        let new_size = memory_expansion!($interp, $offset, $len);
+        if new_size > $interp.memory.len() {
+            $interp.memory.resize(new_size);
+        }
+    }};
+}
+
 macro_rules! pop_address {
     ( $interp:expr, $x1:ident) => {
         if $interp.stack.len() < 1 {
@@ -1,6 +1,6 @@
 use crate::gas;
 use crate::InstructionResult;
-use revm_primitives::{Spec, SpecId::SHANGHAI, U256};
+use revm_primitives::{Spec, U256};
 
 use crate::{interpreter::Interpreter, Host};
 
@@ -15,7 +15,10 @@ pub fn pop(interpreter: &mut Interpreter, _host: &mut dyn Host) {
 /// Introduce a new instruction which pushes the constant value 0 onto the stack
 pub fn push0<SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     // EIP-3855: PUSH0 instruction
-    check!(interpreter, SPEC::enabled(SHANGHAI));
+    check!(
+        interpreter,
+        interpreter.eip_overrides.is_push0_enabled(SPEC::SPEC_ID)
+    );
     gas!(interpreter, gas::BASE);
     if let Err(result) = interpreter.stack.push(U256::ZERO) {
         interpreter.instruction_result = result;
@@ -49,10 +49,9 @@ pub fn codecopy(interpreter: &mut Interpreter, _host: &mut dyn Host) {
         InstructionResult::InvalidOperandOOG
     );
     let code_offset = as_usize_saturated!(code_offset);
-    memory_resize!(interpreter, memory_offset, len);
-
-    // Safety: set_data is unsafe function and memory_resize ensures us that it is safe to call it
-    interpreter.memory.set_data(
+    let new_size = memory_expansion!(interpreter, memory_offset, len);
+    interpreter.memory.resize_and_set_data(
+        new_size,
         memory_offset,
         code_offset,
         len,
@@ -100,12 +99,14 @@ pub fn calldatacopy(interpreter: &mut Interpreter, _host: &mut dyn Host) {
         InstructionResult::InvalidOperandOOG
     );
     let data_offset = as_usize_saturated!(data_offset);
-    memory_resize!(interpreter, memory_offset, len);
-
-    // Safety: set_data is unsafe function and memory_resize ensures us that it is safe to call it
-    interpreter
-        .memory
-        .set_data(memory_offset, data_offset, len, &interpreter.contract.input);
+    let new_size = memory_expansion!(interpreter, memory_offset, len);
+    interpreter.memory.resize_and_set_data(
+        new_size,
+        memory_offset,
+        data_offset,
+        len,
+        &interpreter.contract.input,
+    );
 }
 
 pub fn returndatasize<SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut dyn Host) {
@@ -125,6 +126,7 @@ pub fn returndatacopy<SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut dyn
     let len = as_usize_or_fail!(interpreter, len, InstructionResult::InvalidOperandOOG);
     gas_or_fail!(interpreter, gas::verylowcopy_cost(len as u64));
     let data_offset = as_usize_saturated!(offset);
+    // out of bounds read is an error and has to be checked before memory is touched.
     let (data_end, overflow) = data_offset.overflowing_add(len);
     if overflow || data_end > interpreter.return_data_buffer.len() {
         interpreter.instruction_result = InstructionResult::OutOfOffset;
@@ -136,10 +138,13 @@ pub fn returndatacopy<SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut dyn
             memory_offset,
             InstructionResult::InvalidOperandOOG
         );
-        memory_resize!(interpreter, memory_offset, len);
-        interpreter.memory.set(
+        let new_size = memory_expansion!(interpreter, memory_offset, len);
+        interpreter.memory.resize_and_set_data(
+            new_size,
             memory_offset,
-            &interpreter.return_data_buffer[data_offset..data_end],
+            data_offset,
+            len,
+            &interpreter.return_data_buffer,
         );
     }
 }
@@ -148,3 +153,41 @@ pub fn gas(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     gas!(interpreter, gas::BASE);
     push!(interpreter, U256::from(interpreter.gas.remaining()));
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::alloc::boxed::Box;
+    use crate::primitives::{Bytecode, Bytes, Env, LatestSpec, B160, U256};
+    use crate::{Contract, DummyHost, InstructionResult, Interpreter};
+
+    fn returndatacopy(len: u8) -> Interpreter {
+        // PUSH1 len, PUSH1 0, PUSH1 0, RETURNDATACOPY
+        let code = Bytes::copy_from_slice(&[0x60, len, 0x60, 0x00, 0x60, 0x00, 0x3e]);
+        let contract = Contract::new(
+            Bytes::new(),
+            Bytecode::new_raw(code),
+            B160::zero(),
+            B160::zero(),
+            U256::ZERO,
+        );
+        let mut interpreter = Interpreter::new(Box::new(contract), 100_000, false);
+        interpreter.return_data_buffer = Bytes::from_static(&[1; 32]);
+        interpreter.run::<_, LatestSpec>(&mut DummyHost::new(Env::default()));
+        interpreter
+    }
+
+    #[test]
+    fn returndatacopy_out_of_bounds() {
+        let interpreter = returndatacopy(32);
+        assert_eq!(interpreter.instruction_result, InstructionResult::Stop);
+        assert_eq!(interpreter.memory.data(), &[1; 32]);
+
+        // memory is not expanded when the read is out of bounds.
+        let interpreter = returndatacopy(33);
+        assert_eq!(
+            interpreter.instruction_result,
+            InstructionResult::OutOfOffset
+        );
+        assert!(interpreter.memory.is_empty());
+    }
+}
@@ -8,7 +8,7 @@ pub use contract::Contract;
 pub use memory::Memory;
 pub use stack::Stack;
 
-use crate::primitives::{Bytes, Spec};
+use crate::primitives::{Bytes, EipOverrides, GasSchedule, Spec};
 use crate::{
     alloc::boxed::Box,
     instructions::{eval, InstructionResult},
@@ -47,6 +47,10 @@ pub struct Interpreter {
     /// Memory limit. See [`crate::CfgEnv`].
     #[cfg(feature = "memory_limit")]
     pub memory_limit: u64,
+    /// Gas schedule that overrides the default costs of the spec. See [`crate::CfgEnv`].
+    pub gas_schedule: Option<GasSchedule>,
+    /// Overrides of single EIPs of the spec. See [`crate::CfgEnv`].
+    pub eip_overrides: EipOverrides,
 }
 
 impl Interpreter {
@@ -69,6 +73,8 @@ impl Interpreter {
                 instruction_result: InstructionResult::Continue,
                 is_static,
                 gas: Gas::new(gas_limit),
+                gas_schedule: None,
+                eip_overrides: EipOverrides::default(),
             }
         }
 
@@ -96,6 +102,8 @@ impl Interpreter {
             is_static,
             gas: Gas::new(gas_limit),
             memory_limit,
+            gas_schedule: None,
+            eip_overrides: EipOverrides::default(),
         }
     }
 
@@ -141,6 +149,11 @@ impl Interpreter {
     /// loop steps until we are finished with execution
     pub fn run<H: Host, SPEC: Spec>(&mut self, host: &mut H) -> InstructionResult {
         while self.instruction_result == InstructionResult::Continue {
+            #[cfg(feature = "optional_instruction_limit")]
+            if !host.consume_instruction() {
+                self.instruction_result = InstructionResult::InstructionLimitReached;
+                break;
+            }
             self.step::<H, SPEC>(host)
         }
         self.instruction_result
@@ -149,6 +162,11 @@ impl Interpreter {
     /// loop steps until we are finished with execution
     pub fn run_inspect<H: Host, SPEC: Spec>(&mut self, host: &mut H) -> InstructionResult {
         while self.instruction_result == InstructionResult::Continue {
+            #[cfg(feature = "optional_instruction_limit")]
+            if !host.consume_instruction() {
+                self.instruction_result = InstructionResult::InstructionLimitReached;
+                break;
+            }
             // step
             let ret = host.step(self);
             if ret != InstructionResult::Continue {
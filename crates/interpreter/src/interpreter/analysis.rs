@@ -14,6 +14,8 @@ use revm_primitives::{
 /// The analysis finds and caches valid jump destinations for later execution as an optimization step.
 ///
 /// If the bytecode is already analyzed, it is returned as-is.
+///
+/// EOF containers are not executable yet and are analysed as legacy bytecode.
 pub fn to_analysed(bytecode: Bytecode) -> Bytecode {
     let hash = bytecode.hash;
     let (bytecode, len) = match bytecode.state {
@@ -22,6 +24,15 @@ pub fn to_analysed(bytecode: Bytecode) -> Bytecode {
             let checked = bytecode.to_checked();
             (checked.bytecode, len)
         }
+        BytecodeState::Eof(_) => {
+            let len = bytecode.bytecode.len();
+            let checked = Bytecode {
+                state: BytecodeState::Raw,
+                ..bytecode
+            }
+            .to_checked();
+            (checked.bytecode, len)
+        }
         BytecodeState::Checked { len } => (bytecode.bytecode, len),
         _ => return bytecode,
     };
@@ -93,22 +93,69 @@ impl Memory {
     /// are doing bound checks on data/data_offeset/len and zeroing parts that is not copied.
     #[inline(always)]
     pub fn set_data(&mut self, memory_offset: usize, data_offset: usize, len: usize, data: &[u8]) {
-        if data_offset >= data.len() {
-            // nulify all memory slots
-            for i in &mut self.data[memory_offset..memory_offset + len] {
-                *i = 0;
-            }
+        let copied = copied_len(data_offset, len, data.len());
+        let (copy, padding) = self.data[memory_offset..memory_offset + len].split_at_mut(copied);
+        if copied != 0 {
+            copy.copy_from_slice(&data[data_offset..data_offset + copied]);
+        }
+        padding.fill(0);
+    }
+
+    /// Resize the memory to `new_size` and set memory from data same as [Memory::set_data].
+    ///
+    /// Bytes of the expansion that are overwritten by data are written only once instead of
+    /// being zeroed first. `new_size` smaller than the current size is ignored, and
+    /// `memory_offset + len` is expected to be in the resized memory.
+    #[inline(always)]
+    pub fn resize_and_set_data(
+        &mut self,
+        new_size: usize,
+        memory_offset: usize,
+        data_offset: usize,
+        len: usize,
+        data: &[u8],
+    ) {
+        let old_size = self.data.len();
+        if new_size <= old_size {
+            self.set_data(memory_offset, data_offset, len, data);
             return;
         }
-        let data_end = min(data_offset + len, data.len());
-        let memory_data_end = memory_offset + (data_end - data_offset);
-        self.data[memory_offset..memory_data_end].copy_from_slice(&data[data_offset..data_end]);
-
-        // nulify rest of memory slots
-        // Safety: Memory is assumed to be valid. And it is commented where that assumption is made
-        for i in &mut self.data[memory_data_end..memory_offset + len] {
-            *i = 0;
+        let memory_end = memory_offset + len;
+        debug_assert!(memory_end <= new_size);
+
+        // part of the window that is in the current memory.
+        let split = old_size.clamp(memory_offset, memory_end);
+        if split > memory_offset {
+            self.set_data(memory_offset, data_offset, split - memory_offset, data);
+        }
+
+        // rest is written to the spare capacity. Expansion is laid out as a zeroed gap before
+        // the window, copied data, zero padding of data and a zeroed tail after the window.
+        let window_start = split.max(old_size);
+        let window_end = memory_end.max(window_start);
+        let data_offset = data_offset.saturating_add(window_start - memory_offset);
+        let copied = copied_len(data_offset, window_end - window_start, data.len());
+
+        self.data.reserve(new_size - old_size);
+        let expansion = &mut self.data.spare_capacity_mut()[..new_size - old_size];
+        let (gap, rest) = expansion.split_at_mut(window_start - old_size);
+        let (window, tail) = rest.split_at_mut(window_end - window_start);
+        let (copy, padding) = window.split_at_mut(copied);
+        if copied != 0 {
+            for (byte, value) in copy
+                .iter_mut()
+                .zip(&data[data_offset..data_offset + copied])
+            {
+                byte.write(*value);
+            }
         }
+        for zeroed in [gap, padding, tail] {
+            for byte in zeroed {
+                byte.write(0);
+            }
+        }
+        // Safety: all bytes of the expansion are initialized above.
+        unsafe { self.data.set_len(new_size) };
     }
 
     /// In memory copy given a src, dst, and length
@@ -121,6 +168,12 @@ impl Memory {
     }
 }
 
+/// Number of bytes of `data_offset..data_offset + len` window that are in data bounds.
+#[inline(always)]
+fn copied_len(data_offset: usize, len: usize, data_len: usize) -> usize {
+    min(len, data_len.saturating_sub(data_offset))
+}
+
 /// Rounds up `x` to the closest multiple of 32. If `x % 32 == 0` then `x` is returned.
 #[inline]
 pub(crate) fn next_multiple_of_32(x: usize) -> Option<usize> {
@@ -152,6 +205,61 @@ mod tests {
         assert_eq!(copied_data, &[1, 2, 3, 4]);
     }
 
+    /// Byte by byte resize and copy with zero padding.
+    fn reference_set_data(
+        memory: &mut Vec<u8>,
+        new_size: usize,
+        memory_offset: usize,
+        data_offset: usize,
+        len: usize,
+        data: &[u8],
+    ) {
+        if new_size > memory.len() {
+            memory.resize(new_size, 0);
+        }
+        for i in 0..len {
+            memory[memory_offset + i] = data_offset
+                .checked_add(i)
+                .and_then(|index| data.get(index))
+                .copied()
+                .unwrap_or_default();
+        }
+    }
+
+    #[test]
+    fn test_resize_and_set_data() {
+        proptest::proptest!(|(
+            old_words in 0usize..4,
+            memory_offset in 0usize..160,
+            len in 0usize..160,
+            data_offset in proptest::prop_oneof![0usize..160, proptest::strategy::Just(usize::MAX)],
+            data_len in 0usize..128,
+        )| {
+            let data: Vec<u8> = (1..=data_len).map(|i| i as u8).collect();
+            let mut memory = Memory::new();
+            memory.resize(old_words * 32);
+            memory.data.iter_mut().for_each(|byte| *byte = 0xff);
+            let mut expected = memory.data.clone();
+
+            let new_size = next_multiple_of_32(memory_offset + len).unwrap();
+            memory.resize_and_set_data(new_size, memory_offset, data_offset, len, &data);
+            reference_set_data(&mut expected, new_size, memory_offset, data_offset, len, &data);
+            proptest::prop_assert_eq!(memory.data(), &expected);
+        });
+    }
+
+    #[test]
+    fn test_set_data_padding() {
+        let mut memory = Memory::new();
+        memory.resize(64);
+        memory.data.iter_mut().for_each(|byte| *byte = 0xff);
+
+        memory.set_data(1, 2, 4, &[1, 2, 3, 4]);
+        assert_eq!(memory.get_slice(0, 6), &[0xff, 3, 4, 0, 0, 0xff]);
+        memory.set_data(0, usize::MAX, 2, &[1, 2, 3, 4]);
+        assert_eq!(memory.get_slice(0, 3), &[0, 0, 4]);
+    }
+
     #[test]
     fn test_next_multiple_of_32() {
         // next_multiple_of_32 returns x when it is a multiple of 32
@@ -4,6 +4,11 @@ use crate::{alloc::vec::Vec, InstructionResult};
 pub const STACK_LIMIT: usize = 1024;
 
 /// EVM stack.
+///
+/// Inspectors can modify the stack with [Stack::set]. It is safe to do so inside
+/// `Inspector::step` before the instruction is run, or inside `Inspector::step_end` for the
+/// instruction that follows. Values are otherwise held by the running instruction and
+/// changes could be lost or observed half way.
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stack {
@@ -213,6 +218,13 @@ impl Stack {
         }
     }
 
+    #[inline]
+    /// Returns a view of the top `n` values of the stack, the top of the stack is the last
+    /// element. If `n` is larger than the stack length, the whole stack is returned.
+    pub fn peek_n(&self, n: usize) -> &[U256] {
+        &self.data[self.data.len().saturating_sub(n)..]
+    }
+
     #[inline(always)]
     pub fn dup<const N: usize>(&mut self) -> Option<InstructionResult> {
         let len = self.data.len();
@@ -306,6 +318,8 @@ impl Stack {
     /// Set a value at given index for the stack, where the top of the
     /// stack is at index `0`. If the index is too large,
     /// `StackError::Underflow` is returned.
+    ///
+    /// See [Stack] on when it is safe to be called from an inspector.
     pub fn set(&mut self, no_from_top: usize, val: U256) -> Result<(), InstructionResult> {
         if self.data.len() > no_from_top {
             let len = self.data.len();
@@ -316,3 +330,30 @@ impl Stack {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peek_n_and_set() {
+        let mut stack = Stack::new();
+        for i in 1..=3 {
+            stack.push(U256::from(i)).unwrap();
+        }
+        assert_eq!(stack.peek_n(2), &[U256::from(2), U256::from(3)]);
+        assert_eq!(stack.peek_n(5).len(), 3);
+        assert!(stack.peek_n(0).is_empty());
+
+        stack.set(0, U256::from(30)).unwrap();
+        stack.set(2, U256::from(10)).unwrap();
+        assert_eq!(
+            stack.peek_n(3),
+            &[U256::from(10), U256::from(2), U256::from(30)]
+        );
+        assert_eq!(
+            stack.set(3, U256::ZERO),
+            Err(InstructionResult::StackUnderflow)
+        );
+    }
+}
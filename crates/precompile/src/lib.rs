@@ -195,6 +195,10 @@ impl Precompiles {
         Self::berlin()
     }
 
+    /// Returns precompiles of the given spec.
+    ///
+    /// Every spec has a single instance that is initialized on first use and shared afterwards,
+    /// so this is cheap to call for every new EVM.
     pub fn new(spec: SpecId) -> &'static Self {
         match spec {
             SpecId::HOMESTEAD => Self::homestead(),
@@ -237,3 +241,27 @@ const fn u64_to_b160(x: u64) -> B160 {
         x_bytes[4], x_bytes[5], x_bytes[6], x_bytes[7],
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_instances() {
+        assert!(core::ptr::eq(
+            Precompiles::new(SpecId::BERLIN),
+            Precompiles::new(SpecId::BERLIN)
+        ));
+        assert!(core::ptr::eq(
+            Precompiles::new(SpecId::LATEST),
+            Precompiles::berlin()
+        ));
+
+        let homestead = Precompiles::new(SpecId::HOMESTEAD);
+        assert_eq!(homestead.len(), 4);
+        assert!(homestead.contains(&u64_to_b160(1)));
+        assert!(!homestead.contains(&u64_to_b160(5)));
+        assert_eq!(Precompiles::new(SpecId::BYZANTIUM).len(), 8);
+        assert_eq!(Precompiles::new(SpecId::BERLIN).len(), 9);
+    }
+}
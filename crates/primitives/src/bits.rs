@@ -70,6 +70,9 @@ impl From<B256> for primitive_types::U256 {
     }
 }
 
+/// Storage keys and values are converted between [B256] and [ruint::aliases::U256] only with
+/// these two impls, both use big-endian byte order, so `B256` bytes are the same as the 32 bytes
+/// pushed on the stack by `PUSH32`.
 impl From<ruint::aliases::U256> for B256 {
     fn from(fr: ruint::aliases::U256) -> Self {
         B256(fr.to_be_bytes())
@@ -323,6 +326,31 @@ mod serialize {
 mod tests {
     use super::*;
 
+    #[test]
+    fn u256_conversion_is_big_endian() {
+        use ruint::aliases::U256;
+
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut high = [0u8; 32];
+        high[0] = 1;
+        let mut mixed = [0u8; 32];
+        mixed[30] = 0x12;
+        mixed[31] = 0x34;
+
+        for (value, bytes) in [
+            (U256::ZERO, [0u8; 32]),
+            (U256::from(1), one),
+            (U256::from(0x1234), mixed),
+            (U256::from(1) << 248, high),
+            (U256::MAX, [0xff; 32]),
+        ] {
+            let key = B256::from(value);
+            assert_eq!(key, B256(bytes));
+            assert_eq!(U256::from(key), value);
+        }
+    }
+
     #[test]
     fn arbitrary() {
         proptest::proptest!(|(_v1: B160, _v2: B256)| {});
@@ -1,9 +1,16 @@
+mod eof;
+
 use crate::{keccak256, B256, KECCAK_EMPTY};
 use alloc::{sync::Arc, vec, vec::Vec};
 use bitvec::prelude::{bitvec, Lsb0};
 use bitvec::vec::BitVec;
 use bytes::Bytes;
 
+pub use eof::{
+    BytecodeDecodeError, EofHeader, TypesSection, EOF_MAGIC, EOF_MAX_CODE_SECTIONS,
+    EOF_MAX_SECTION_IO, EOF_MAX_STACK_HEIGHT, EOF_VERSION,
+};
+
 /// A map of valid `jump` destinations.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -30,10 +37,22 @@ impl JumpMap {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BytecodeState {
     Raw,
-    Checked { len: usize },
-    Analysed { len: usize, jump_map: JumpMap },
+    Checked {
+        len: usize,
+    },
+    Analysed {
+        len: usize,
+        jump_map: JumpMap,
+    },
+    /// Validated EOF container. Bytecode holds the whole container as is.
+    Eof(Arc<EofHeader>),
 }
 
+/// Contract bytecode together with its hash and analysis state.
+///
+/// Cloning is cheap: bytes are reference counted and jump map and EOF header are behind
+/// [Arc], so clones of the same bytecode share their allocations. Caches can store
+/// `Bytecode` by value without wrapping it in another [Arc].
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bytecode {
@@ -87,6 +106,19 @@ impl Bytecode {
         }
     }
 
+    /// Create new EOF bytecode, validating container layout.
+    ///
+    /// EOF execution is not supported yet, if such bytecode is executed it is analysed as legacy
+    /// bytecode.
+    pub fn new_eof(bytecode: Bytes) -> Result<Self, BytecodeDecodeError> {
+        let header = EofHeader::decode(&bytecode)?;
+        Ok(Self {
+            hash: keccak256(&bytecode),
+            bytecode,
+            state: BytecodeState::Eof(Arc::new(header)),
+        })
+    }
+
     /// Create new checked bytecode
     ///
     /// # Safety
@@ -111,7 +143,7 @@ impl Bytecode {
 
     pub fn original_bytes(&self) -> Bytes {
         match self.state {
-            BytecodeState::Raw => self.bytecode.clone(),
+            BytecodeState::Raw | BytecodeState::Eof(_) => self.bytecode.clone(),
             BytecodeState::Checked { len } | BytecodeState::Analysed { len, .. } => {
                 self.bytecode.slice(0..len)
             }
@@ -126,9 +158,32 @@ impl Bytecode {
         &self.state
     }
 
+    /// Returns true if bytecode is EOF container.
+    pub fn is_eof(&self) -> bool {
+        matches!(self.state, BytecodeState::Eof(_))
+    }
+
+    /// Returns code section at `index` if bytecode is EOF container.
+    pub fn code_section(&self, index: usize) -> Option<Bytes> {
+        match &self.state {
+            BytecodeState::Eof(header) => header
+                .code_section_range(index)
+                .map(|range| self.bytecode.slice(range)),
+            _ => None,
+        }
+    }
+
+    /// Returns data section if bytecode is EOF container.
+    pub fn data_section(&self) -> Option<Bytes> {
+        match &self.state {
+            BytecodeState::Eof(header) => Some(self.bytecode.slice(header.data_section_range())),
+            _ => None,
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         match self.state {
-            BytecodeState::Raw => self.bytecode.is_empty(),
+            BytecodeState::Raw | BytecodeState::Eof(_) => self.bytecode.is_empty(),
             BytecodeState::Checked { len } => len == 0,
             BytecodeState::Analysed { len, .. } => len == 0,
         }
@@ -136,7 +191,7 @@ impl Bytecode {
 
     pub fn len(&self) -> usize {
         match self.state {
-            BytecodeState::Raw => self.bytecode.len(),
+            BytecodeState::Raw | BytecodeState::Eof(_) => self.bytecode.len(),
             BytecodeState::Checked { len, .. } => len,
             BytecodeState::Analysed { len, .. } => len,
         }
@@ -158,3 +213,57 @@ impl Bytecode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn eof_bytecode() {
+        let raw = Bytes::from_static(&hex!(
+            "EF00 01 010008 020002 0003 0001 030002 00 00000001 01000001 600000 E4 AABB"
+        ));
+        let bytecode = Bytecode::new_eof(raw.clone()).unwrap();
+        assert!(bytecode.is_eof());
+        assert_eq!(bytecode.hash(), keccak256(&raw));
+        assert_eq!(bytecode.original_bytes(), raw);
+        assert_eq!(bytecode.len(), raw.len());
+        assert_eq!(&bytecode.code_section(0).unwrap()[..], &hex!("600000"));
+        assert_eq!(&bytecode.code_section(1).unwrap()[..], &hex!("E4"));
+        assert_eq!(bytecode.code_section(2), None);
+        assert_eq!(&bytecode.data_section().unwrap()[..], &hex!("AABB"));
+
+        let legacy = Bytecode::new_raw(Bytes::from_static(&hex!("600000")));
+        assert!(!legacy.is_eof());
+        assert_eq!(legacy.code_section(0), None);
+        assert_eq!(legacy.data_section(), None);
+    }
+
+    #[test]
+    fn eof_bytecode_invalid() {
+        assert_eq!(
+            Bytecode::new_eof(Bytes::from_static(&hex!("600000"))),
+            Err(BytecodeDecodeError::InvalidMagic)
+        );
+    }
+
+    #[test]
+    fn clone_shares_allocations() {
+        let bytecode = Bytecode::new();
+        let clone = bytecode.clone();
+        assert_eq!(bytecode.bytecode.as_ptr(), clone.bytecode.as_ptr());
+        match (&bytecode.state, &clone.state) {
+            (
+                BytecodeState::Analysed { jump_map, .. },
+                BytecodeState::Analysed {
+                    jump_map: cloned, ..
+                },
+            ) => assert!(Arc::ptr_eq(&jump_map.0, &cloned.0)),
+            _ => unreachable!(),
+        }
+
+        let raw = Bytecode::new_raw(Bytes::from(vec![0x60, 0x00]));
+        assert_eq!(raw.bytecode.as_ptr(), raw.clone().bytecode.as_ptr());
+    }
+}
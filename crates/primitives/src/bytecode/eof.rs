@@ -0,0 +1,363 @@
+use alloc::vec::Vec;
+use core::{fmt, ops::Range};
+
+/// EOF magic prefix (EIP-3540).
+pub const EOF_MAGIC: [u8; 2] = [0xEF, 0x00];
+/// Only supported EOF version.
+pub const EOF_VERSION: u8 = 0x01;
+
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_DATA: u8 = 0x03;
+const TERMINATOR: u8 = 0x00;
+
+/// Maximum number of code sections (EIP-4750).
+pub const EOF_MAX_CODE_SECTIONS: usize = 1024;
+/// Maximum value of inputs and outputs in type section (EIP-4750).
+pub const EOF_MAX_SECTION_IO: u8 = 0x7F;
+/// Maximum stack height of a code section (EIP-5450).
+pub const EOF_MAX_STACK_HEIGHT: u16 = 1023;
+
+/// Errors returned when decoding EOF container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BytecodeDecodeError {
+    /// Container does not start with `0xEF00`.
+    InvalidMagic,
+    /// Version is not supported.
+    InvalidVersion(u8),
+    /// Input ended before header was complete.
+    IncompleteHeader,
+    /// Type section header is missing.
+    MissingTypesHeader,
+    /// Code section header is missing.
+    MissingCodeHeader,
+    /// Data section header is missing.
+    MissingDataHeader,
+    /// Header is not terminated with zero byte.
+    MissingTerminator,
+    /// There needs to be at least one code section.
+    ZeroCodeSections,
+    /// Number of code sections is above [EOF_MAX_CODE_SECTIONS].
+    TooManyCodeSections,
+    /// Code section is declared with zero size.
+    ZeroSizeCodeSection,
+    /// Type section size is not four bytes per code section.
+    InvalidTypesSize,
+    /// First code section needs to have zero inputs and zero outputs.
+    InvalidFirstSectionType,
+    /// Inputs or outputs of code section are above [EOF_MAX_SECTION_IO].
+    InvalidSectionType,
+    /// Max stack height is above [EOF_MAX_STACK_HEIGHT].
+    InvalidMaxStackHeight,
+    /// Body is smaller than sizes declared in header.
+    IncompleteBody,
+    /// There are bytes after the data section.
+    DanglingBytes,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BytecodeDecodeError {}
+
+impl fmt::Display for BytecodeDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidVersion(version) => write!(f, "Invalid EOF version: {}", version),
+            _ => write!(f, "Invalid EOF container: {:?}", self),
+        }
+    }
+}
+
+/// Entry of EOF type section.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypesSection {
+    pub inputs: u8,
+    pub outputs: u8,
+    pub max_stack_height: u16,
+}
+
+/// Section layout of EOF container.
+///
+/// Sections are not copied, ranges returned by this type index into the raw container.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EofHeader {
+    /// Parsed entries of the type section, one per code section.
+    pub types: Vec<TypesSection>,
+    /// Sizes of code sections.
+    pub code_sizes: Vec<u16>,
+    /// Size of data section.
+    pub data_size: u16,
+}
+
+impl EofHeader {
+    /// Size of encoded header in bytes.
+    pub fn header_size(&self) -> usize {
+        // magic, version, types header, code header, data header, terminator.
+        2 + 1 + 3 + 3 + 2 * self.code_sizes.len() + 3 + 1
+    }
+
+    /// Size of the type section.
+    pub fn types_size(&self) -> usize {
+        4 * self.types.len()
+    }
+
+    /// Size of the whole container.
+    pub fn container_size(&self) -> usize {
+        self.header_size()
+            + self.types_size()
+            + self
+                .code_sizes
+                .iter()
+                .map(|size| *size as usize)
+                .sum::<usize>()
+            + self.data_size as usize
+    }
+
+    /// Range of code section `index` inside the container.
+    pub fn code_section_range(&self, index: usize) -> Option<Range<usize>> {
+        let size = *self.code_sizes.get(index)? as usize;
+        let start = self.header_size()
+            + self.types_size()
+            + self.code_sizes[..index]
+                .iter()
+                .map(|size| *size as usize)
+                .sum::<usize>();
+        Some(start..start + size)
+    }
+
+    /// Range of data section inside the container.
+    pub fn data_section_range(&self) -> Range<usize> {
+        let end = self.container_size();
+        end - self.data_size as usize..end
+    }
+
+    /// Decodes and validates container layout.
+    ///
+    /// Only the container format is validated, code sections are not checked for valid
+    /// instructions.
+    pub fn decode(raw: &[u8]) -> Result<Self, BytecodeDecodeError> {
+        if !raw.starts_with(&EOF_MAGIC) {
+            return Err(BytecodeDecodeError::InvalidMagic);
+        }
+        let mut input = &raw[EOF_MAGIC.len()..];
+
+        let version = read_u8(&mut input)?;
+        if version != EOF_VERSION {
+            return Err(BytecodeDecodeError::InvalidVersion(version));
+        }
+
+        if read_u8(&mut input)? != KIND_TYPES {
+            return Err(BytecodeDecodeError::MissingTypesHeader);
+        }
+        let types_size = read_u16(&mut input)? as usize;
+
+        if read_u8(&mut input)? != KIND_CODE {
+            return Err(BytecodeDecodeError::MissingCodeHeader);
+        }
+        let num_code_sections = read_u16(&mut input)? as usize;
+        if num_code_sections == 0 {
+            return Err(BytecodeDecodeError::ZeroCodeSections);
+        }
+        if num_code_sections > EOF_MAX_CODE_SECTIONS {
+            return Err(BytecodeDecodeError::TooManyCodeSections);
+        }
+        let mut code_sizes = Vec::with_capacity(num_code_sections);
+        for _ in 0..num_code_sections {
+            let size = read_u16(&mut input)?;
+            if size == 0 {
+                return Err(BytecodeDecodeError::ZeroSizeCodeSection);
+            }
+            code_sizes.push(size);
+        }
+        if types_size != 4 * num_code_sections {
+            return Err(BytecodeDecodeError::InvalidTypesSize);
+        }
+
+        if read_u8(&mut input)? != KIND_DATA {
+            return Err(BytecodeDecodeError::MissingDataHeader);
+        }
+        let data_size = read_u16(&mut input)?;
+
+        if read_u8(&mut input)? != TERMINATOR {
+            return Err(BytecodeDecodeError::MissingTerminator);
+        }
+
+        let body_size = types_size
+            + code_sizes.iter().map(|size| *size as usize).sum::<usize>()
+            + data_size as usize;
+        if input.len() < body_size {
+            return Err(BytecodeDecodeError::IncompleteBody);
+        }
+        if input.len() > body_size {
+            return Err(BytecodeDecodeError::DanglingBytes);
+        }
+
+        let types = input[..types_size]
+            .chunks_exact(4)
+            .map(|chunk| TypesSection {
+                inputs: chunk[0],
+                outputs: chunk[1],
+                max_stack_height: u16::from_be_bytes([chunk[2], chunk[3]]),
+            })
+            .collect::<Vec<_>>();
+        if types[0].inputs != 0 || types[0].outputs != 0 {
+            return Err(BytecodeDecodeError::InvalidFirstSectionType);
+        }
+        for section in &types {
+            if section.inputs > EOF_MAX_SECTION_IO || section.outputs > EOF_MAX_SECTION_IO {
+                return Err(BytecodeDecodeError::InvalidSectionType);
+            }
+            if section.max_stack_height > EOF_MAX_STACK_HEIGHT {
+                return Err(BytecodeDecodeError::InvalidMaxStackHeight);
+            }
+        }
+
+        Ok(Self {
+            types,
+            code_sizes,
+            data_size,
+        })
+    }
+}
+
+#[inline]
+fn read_u8(input: &mut &[u8]) -> Result<u8, BytecodeDecodeError> {
+    let (first, rest) = input
+        .split_first()
+        .ok_or(BytecodeDecodeError::IncompleteHeader)?;
+    *input = rest;
+    Ok(*first)
+}
+
+#[inline]
+fn read_u16(input: &mut &[u8]) -> Result<u16, BytecodeDecodeError> {
+    Ok(u16::from_be_bytes([read_u8(input)?, read_u8(input)?]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn decode_valid() {
+        // one code section with STOP and two bytes of data.
+        let header = EofHeader::decode(&hex!(
+            "EF00 01 010004 0200010001 030002 00 00000000 00 AABB"
+        ))
+        .unwrap();
+        assert_eq!(header.code_sizes, vec![1]);
+        assert_eq!(header.data_size, 2);
+        assert_eq!(header.header_size(), 15);
+        assert_eq!(header.code_section_range(0), Some(19..20));
+        assert_eq!(header.code_section_range(1), None);
+        assert_eq!(header.data_section_range(), 20..22);
+
+        // two code sections and empty data.
+        let header = EofHeader::decode(&hex!(
+            "EF00 01 010008 020002 0003 0001 030000 00 00000001 01000001 600000 E4"
+        ))
+        .unwrap();
+        assert_eq!(
+            header.types[1],
+            TypesSection {
+                inputs: 1,
+                outputs: 0,
+                max_stack_height: 1
+            }
+        );
+        assert_eq!(header.code_section_range(1), Some(28..29));
+        assert_eq!(header.data_section_range(), 29..29);
+    }
+
+    #[test]
+    fn decode_invalid() {
+        use BytecodeDecodeError::*;
+        let cases: &[(&[u8], BytecodeDecodeError)] = &[
+            (&[], InvalidMagic),
+            (&hex!("EF"), InvalidMagic),
+            (&hex!("EF01 01"), InvalidMagic),
+            (&hex!("EF00"), IncompleteHeader),
+            (
+                &hex!("EF00 02 010004 0200010001 030000 00 00000000 00"),
+                InvalidVersion(2),
+            ),
+            (&hex!("EF00 01 020004"), MissingTypesHeader),
+            (&hex!("EF00 01 010004 03"), MissingCodeHeader),
+            (&hex!("EF00 01 010004 020000"), ZeroCodeSections),
+            (&hex!("EF00 01 010004 0204010001"), TooManyCodeSections),
+            (&hex!("EF00 01 010004 0200010000"), ZeroSizeCodeSection),
+            (&hex!("EF00 01 010004 0200010001"), IncompleteHeader),
+            (
+                &hex!("EF00 01 010008 0200010001 030000 00"),
+                InvalidTypesSize,
+            ),
+            (
+                &hex!("EF00 01 010004 0200010001 040000 00"),
+                MissingDataHeader,
+            ),
+            (
+                &hex!("EF00 01 010004 0200010001 030000 01"),
+                MissingTerminator,
+            ),
+            (&hex!("EF00 01 010004 0200010001 030000"), IncompleteHeader),
+            (
+                &hex!("EF00 01 010004 0200010001 030001 00 00000000 00"),
+                IncompleteBody,
+            ),
+            (
+                &hex!("EF00 01 010004 0200010001 030000 00 00000000 00 00"),
+                DanglingBytes,
+            ),
+            (
+                &hex!("EF00 01 010004 0200010001 030000 00 01000000 00"),
+                InvalidFirstSectionType,
+            ),
+            (
+                &hex!("EF00 01 010008 0200020001 0001 030000 00 00000000 80000000 00 00"),
+                InvalidSectionType,
+            ),
+            (
+                &hex!("EF00 01 010004 0200010001 030000 00 00000400 00"),
+                InvalidMaxStackHeight,
+            ),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(EofHeader::decode(raw), Err(*expected), "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn container_layout_vectors() {
+        let vectors = include_str!("../../testdata/eof/container_layout.txt");
+        let mut count = 0;
+        for line in vectors.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split('|').map(str::trim).collect::<Vec<_>>();
+            let [name, code, result] = fields[..] else {
+                panic!("malformed vector: {line}");
+            };
+            let code = code.split_whitespace().collect::<alloc::string::String>();
+            let raw = hex::decode(code).unwrap();
+
+            match (EofHeader::decode(&raw), result) {
+                (Ok(_), "valid") => {}
+                (Err(err), expected) => {
+                    let err = alloc::format!("{:?}", err);
+                    let variant = err.split('(').next().unwrap();
+                    assert_eq!(variant, expected, "vector {name}");
+                }
+                (Ok(header), expected) => {
+                    panic!("vector {name}: expected {expected}, decoded {header:?}")
+                }
+            }
+            count += 1;
+        }
+        assert_eq!(count, 57);
+    }
+}
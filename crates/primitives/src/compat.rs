@@ -0,0 +1,44 @@
+//! Conversions between revm types and types of other Ethereum libraries.
+//!
+//! Enabled with `alloy-compat` and `ethers-compat` features. Conversions of [crate::B160],
+//! [crate::B256] and [crate::U256] from `primitive-types`, used by ethers, are always available.
+
+#[cfg(feature = "alloy-compat")]
+mod alloy;
+#[cfg(feature = "ethers-compat")]
+mod ethers;
+
+#[cfg(feature = "ethers-compat")]
+pub use self::ethers::{access_list_from_ethers, access_list_to_ethers};
+
+use core::fmt;
+
+/// Errors of conversions that would lose information.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompatError {
+    /// Recipient is an ENS name that needs to be resolved first.
+    EnsName,
+    /// Gas limit does not fit into `u64`.
+    GasLimitOverflow,
+    /// Nonce does not fit into `u64`.
+    NonceOverflow,
+    /// Block has no number, it is pending.
+    MissingBlockNumber,
+    /// Block of a spec after the merge has no mix hash to use as prevrandao.
+    MissingPrevrandao,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CompatError {}
+
+impl fmt::Display for CompatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompatError::EnsName => write!(f, "ENS name recipient is not resolved"),
+            CompatError::GasLimitOverflow => write!(f, "Gas limit does not fit into u64"),
+            CompatError::NonceOverflow => write!(f, "Nonce does not fit into u64"),
+            CompatError::MissingBlockNumber => write!(f, "Block number is missing"),
+            CompatError::MissingPrevrandao => write!(f, "Mix hash for prevrandao is missing"),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Conversions to and from `alloy-primitives`.
+//!
+//! `U256` of both crates is the `ruint` type and `alloy_primitives::Bytes` converts from and to
+//! [bytes::Bytes], so only addresses and hashes need conversions.
+
+use crate::{B160, B256};
+use alloy_primitives::{Address, B256 as AlloyB256};
+
+impl From<Address> for B160 {
+    fn from(address: Address) -> Self {
+        B160(address.0 .0)
+    }
+}
+
+impl From<B160> for Address {
+    fn from(address: B160) -> Self {
+        Address::from(address.0)
+    }
+}
+
+impl From<AlloyB256> for B256 {
+    fn from(hash: AlloyB256) -> Self {
+        B256(hash.0)
+    }
+}
+
+impl From<B256> for AlloyB256 {
+    fn from(hash: B256) -> Self {
+        AlloyB256::from(hash.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let address = B160::from_low_u64_be(0x1234);
+        let alloy_address = Address::from(address);
+        assert_eq!(alloy_address.0 .0, address.0);
+        assert_eq!(B160::from(alloy_address), address);
+
+        let hash = B256::repeat_byte(0xab);
+        let alloy_hash = AlloyB256::from(hash);
+        assert_eq!(alloy_hash.0, hash.0);
+        assert_eq!(B256::from(alloy_hash), hash);
+
+        // both crates need to resolve to the same ruint version for U256 to be one type.
+        let value: alloy_primitives::U256 = crate::U256::from(0x1234);
+        assert_eq!(value, alloy_primitives::U256::from(0x1234));
+    }
+}
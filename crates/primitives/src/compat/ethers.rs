@@ -0,0 +1,264 @@
+//! Conversions to and from `ethers-core` types.
+
+use super::CompatError;
+use crate::{BlockEnv, Log, SpecId, TransactTo, TxEnv, B160, B256, U256};
+use alloc::vec::Vec;
+use ethers_core::types::{
+    transaction::{
+        eip2718::TypedTransaction,
+        eip2930::{AccessList, AccessListItem},
+    },
+    Block, Log as EthersLog, NameOrAddress, U256 as EthersU256,
+};
+
+impl From<EthersLog> for Log {
+    fn from(log: EthersLog) -> Self {
+        Log {
+            address: log.address.into(),
+            topics: log.topics.into_iter().map(Into::into).collect(),
+            data: log.data.0,
+        }
+    }
+}
+
+/// Block and transaction fields of the created log are not set.
+impl From<Log> for EthersLog {
+    fn from(log: Log) -> Self {
+        EthersLog {
+            address: log.address.into(),
+            topics: log.topics.into_iter().map(Into::into).collect(),
+            data: log.data.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Converts access list to the [TxEnv::access_list] format.
+pub fn access_list_from_ethers(access_list: &AccessList) -> Vec<(B160, Vec<U256>)> {
+    access_list
+        .0
+        .iter()
+        .map(|item| {
+            let slots = item
+                .storage_keys
+                .iter()
+                .map(|key| U256::from_be_bytes(key.0))
+                .collect();
+            (item.address.into(), slots)
+        })
+        .collect()
+}
+
+/// Converts access list in the [TxEnv::access_list] format to ethers access list.
+pub fn access_list_to_ethers(access_list: &[(B160, Vec<U256>)]) -> AccessList {
+    AccessList(
+        access_list
+            .iter()
+            .map(|(address, slots)| AccessListItem {
+                address: (*address).into(),
+                storage_keys: slots.iter().map(|slot| B256::from(*slot).into()).collect(),
+            })
+            .collect(),
+    )
+}
+
+fn to_u64(value: &EthersU256, error: CompatError) -> Result<u64, CompatError> {
+    let value: U256 = (*value).into();
+    u64::try_from(value).map_err(|_| error)
+}
+
+/// Fields that are not set in the request keep their [TxEnv::default] values.
+impl TryFrom<&TypedTransaction> for TxEnv {
+    type Error = CompatError;
+
+    fn try_from(tx: &TypedTransaction) -> Result<Self, Self::Error> {
+        let mut env = TxEnv::default();
+        if let Some(from) = tx.from() {
+            env.caller = (*from).into();
+        }
+        if let Some(gas) = tx.gas() {
+            env.gas_limit = to_u64(gas, CompatError::GasLimitOverflow)?;
+        }
+        // max fee per gas for EIP-1559 transactions.
+        if let Some(gas_price) = tx.gas_price() {
+            env.gas_price = gas_price.into();
+        }
+        if let TypedTransaction::Eip1559(tx) = tx {
+            env.gas_priority_fee = tx.max_priority_fee_per_gas.map(Into::into);
+        }
+        env.transact_to = match tx.to() {
+            Some(NameOrAddress::Address(to)) => TransactTo::Call((*to).into()),
+            Some(NameOrAddress::Name(_)) => return Err(CompatError::EnsName),
+            None => TransactTo::create(),
+        };
+        if let Some(value) = tx.value() {
+            env.value = (*value).into();
+        }
+        if let Some(data) = tx.data() {
+            env.data = data.0.clone();
+        }
+        env.chain_id = tx.chain_id().map(|chain_id| chain_id.as_u64());
+        env.nonce = tx
+            .nonce()
+            .map(|nonce| to_u64(nonce, CompatError::NonceOverflow))
+            .transpose()?;
+        if let Some(access_list) = tx.access_list() {
+            env.access_list = access_list_from_ethers(access_list);
+        }
+        Ok(env)
+    }
+}
+
+impl BlockEnv {
+    /// Creates block environment from the block header.
+    ///
+    /// After the merge mix hash of the block is used as prevrandao, before it the difficulty
+    /// is used. Blob gas fields are left at their defaults.
+    pub fn try_from_ethers_block<TX>(
+        block: &Block<TX>,
+        spec_id: SpecId,
+    ) -> Result<Self, CompatError> {
+        let number = block.number.ok_or(CompatError::MissingBlockNumber)?;
+        let prevrandao = if SpecId::enabled(spec_id, SpecId::MERGE) {
+            Some(
+                block
+                    .mix_hash
+                    .map(Into::into)
+                    .ok_or(CompatError::MissingPrevrandao)?,
+            )
+        } else {
+            None
+        };
+        Ok(BlockEnv {
+            number: U256::from(number.as_u64()),
+            coinbase: block.author.map(Into::into).unwrap_or_default(),
+            timestamp: block.timestamp.into(),
+            difficulty: block.difficulty.into(),
+            prevrandao,
+            basefee: block.base_fee_per_gas.map(Into::into).unwrap_or_default(),
+            gas_limit: block.gas_limit.into(),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bytes;
+    use ethers_core::types::{Eip1559TransactionRequest, TransactionRequest, H160, H256, U64};
+
+    #[test]
+    fn log_round_trip() {
+        let log = Log {
+            address: B160::from_low_u64_be(0x1234),
+            topics: vec![B256::repeat_byte(1), B256::repeat_byte(2)],
+            data: Bytes::from_static(&[1, 2, 3]),
+        };
+        let ethers_log = EthersLog::from(log.clone());
+        assert_eq!(ethers_log.topics[1], H256::repeat_byte(2));
+        assert_eq!(Log::from(ethers_log), log);
+    }
+
+    #[test]
+    fn access_list_round_trip() {
+        let access_list = vec![
+            (B160::from_low_u64_be(1), vec![U256::from(1), U256::MAX]),
+            (B160::from_low_u64_be(2), vec![]),
+        ];
+        let ethers_access_list = access_list_to_ethers(&access_list);
+        assert_eq!(
+            ethers_access_list.0[0].storage_keys[0],
+            H256::from_low_u64_be(1)
+        );
+        assert_eq!(access_list_from_ethers(&ethers_access_list), access_list);
+    }
+
+    #[test]
+    fn tx_env_from_request() {
+        let (from, to) = (B160::from_low_u64_be(0x1000), B160::from_low_u64_be(0x2000));
+        let access_list = vec![(to, vec![U256::from(7)])];
+        let request: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(H160::from(from))
+            .to(H160::from(to))
+            .value(5)
+            .gas(21_000)
+            .max_fee_per_gas(10)
+            .max_priority_fee_per_gas(2)
+            .nonce(3)
+            .chain_id(1)
+            .data(vec![0xaa])
+            .access_list(access_list_to_ethers(&access_list))
+            .into();
+
+        let env = TxEnv::try_from(&request).unwrap();
+        assert_eq!(env.caller, from);
+        assert!(matches!(env.transact_to, TransactTo::Call(address) if address == to));
+        assert_eq!(env.value, U256::from(5));
+        assert_eq!(env.gas_limit, 21_000);
+        assert_eq!(env.gas_price, U256::from(10));
+        assert_eq!(env.gas_priority_fee, Some(U256::from(2)));
+        assert_eq!(env.nonce, Some(3));
+        assert_eq!(env.chain_id, Some(1));
+        assert_eq!(env.data, Bytes::from_static(&[0xaa]));
+        assert_eq!(env.access_list, access_list);
+
+        // legacy creation
+        let request: TypedTransaction = TransactionRequest::new().gas_price(7).into();
+        let env = TxEnv::try_from(&request).unwrap();
+        assert!(env.transact_to.is_create());
+        assert_eq!(env.gas_price, U256::from(7));
+        assert_eq!(env.gas_priority_fee, None);
+        assert_eq!(env.gas_limit, u64::MAX);
+
+        let request: TypedTransaction = TransactionRequest::new().to("vitalik.eth").into();
+        assert_eq!(TxEnv::try_from(&request).err(), Some(CompatError::EnsName));
+        let request: TypedTransaction = TransactionRequest::new()
+            .nonce(EthersU256::from(u64::MAX) + 1)
+            .into();
+        assert_eq!(
+            TxEnv::try_from(&request).err(),
+            Some(CompatError::NonceOverflow)
+        );
+    }
+
+    #[test]
+    fn block_env_from_block() {
+        let block = Block::<H256> {
+            number: Some(U64::from(10)),
+            author: Some(B160::from_low_u64_be(0xc0).into()),
+            timestamp: 1_000.into(),
+            difficulty: 2.into(),
+            mix_hash: Some(H256::repeat_byte(3)),
+            base_fee_per_gas: Some(7.into()),
+            gas_limit: 30_000_000.into(),
+            ..Default::default()
+        };
+
+        let merge = BlockEnv::try_from_ethers_block(&block, SpecId::MERGE).unwrap();
+        assert_eq!(merge.number, U256::from(10));
+        assert_eq!(merge.coinbase, B160::from_low_u64_be(0xc0));
+        assert_eq!(merge.timestamp, U256::from(1_000));
+        assert_eq!(merge.prevrandao, Some(B256::repeat_byte(3)));
+        assert_eq!(merge.basefee, U256::from(7));
+        assert_eq!(merge.gas_limit, U256::from(30_000_000));
+
+        let london = BlockEnv::try_from_ethers_block(&block, SpecId::LONDON).unwrap();
+        assert_eq!(london.difficulty, U256::from(2));
+        assert_eq!(london.prevrandao, None);
+
+        let pending = Block::<H256>::default();
+        assert_eq!(
+            BlockEnv::try_from_ethers_block(&pending, SpecId::LONDON).err(),
+            Some(CompatError::MissingBlockNumber)
+        );
+        let no_mix_hash = Block::<H256> {
+            mix_hash: None,
+            ..block
+        };
+        assert_eq!(
+            BlockEnv::try_from_ethers_block(&no_mix_hash, SpecId::MERGE).err(),
+            Some(CompatError::MissingPrevrandao)
+        );
+    }
+}
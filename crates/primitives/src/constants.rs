@@ -1,3 +1,5 @@
+use crate::B160;
+
 /// Interpreter stack limit
 pub const STACK_LIMIT: u64 = 1024;
 /// EVM call stack limit
@@ -11,3 +13,32 @@ pub const MAX_CODE_SIZE: usize = 0x6000;
 ///
 /// Limit of maximum initcode size is 2 * MAX_CODE_SIZE
 pub const MAX_INITCODE_SIZE: usize = 2 * MAX_CODE_SIZE;
+
+/// Number of most recent block hashes reachable by the `BLOCKHASH` opcode.
+pub const BLOCK_HASH_HISTORY: usize = 256;
+
+/// EIP-4844: Shard Blob Transactions
+///
+/// Gas consumption of a single data blob.
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+/// Target number of blobs per block.
+pub const TARGET_BLOB_NUMBER_PER_BLOCK: u64 = 3;
+/// Maximum number of blobs per block.
+pub const MAX_BLOB_NUMBER_PER_BLOCK: u64 = 2 * TARGET_BLOB_NUMBER_PER_BLOCK;
+/// Target consumable blob gas per block.
+pub const TARGET_BLOB_GAS_PER_BLOCK: u64 = TARGET_BLOB_NUMBER_PER_BLOCK * GAS_PER_BLOB;
+/// Maximum consumable blob gas per block.
+pub const MAX_BLOB_GAS_PER_BLOCK: u64 = MAX_BLOB_NUMBER_PER_BLOCK * GAS_PER_BLOB;
+/// Minimum gas price for a data blob.
+pub const MIN_BLOB_GASPRICE: u64 = 1;
+/// Controls the maximum rate of change of the blob gas price.
+pub const BLOB_GASPRICE_UPDATE_FRACTION: u64 = 3338477;
+
+/// Caller of system calls that are executed outside of transactions, for example EIP-4788
+/// beacon root updates.
+pub const SYSTEM_ADDRESS: B160 = B160([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xfe,
+]);
+/// Gas limit of system calls.
+pub const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
@@ -2,7 +2,7 @@ pub mod components;
 
 use crate::AccountInfo;
 use crate::U256;
-use crate::{Account, Bytecode};
+use crate::{Account, Bytecode, Bytes};
 use crate::{B160, B256};
 use auto_impl::auto_impl;
 use hashbrown::HashMap as Map;
@@ -21,6 +21,20 @@ pub trait Database {
     /// Get storage value of address at index.
     fn storage(&mut self, address: B160, index: U256) -> Result<U256, Self::Error>;
 
+    /// Get at most `len` first bytes of the code with the hash.
+    ///
+    /// Default implementation loads the whole code with [Database::code_by_hash]. Databases
+    /// that can read a part of the code should override it.
+    fn code_prefix(&mut self, code_hash: B256, len: usize) -> Result<Bytes, Self::Error> {
+        let code = self.code_by_hash(code_hash)?.original_bytes();
+        Ok(code.slice(..len.min(code.len())))
+    }
+
+    /// Get storage value of address at index, with index and value as big-endian [B256].
+    fn storage_b256(&mut self, address: B160, index: B256) -> Result<B256, Self::Error> {
+        self.storage(address, index.into()).map(Into::into)
+    }
+
     // History related
     fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error>;
 }
@@ -42,6 +56,20 @@ pub trait DatabaseRef {
     /// Get storage value of address at index.
     fn storage(&self, address: B160, index: U256) -> Result<U256, Self::Error>;
 
+    /// Get at most `len` first bytes of the code with the hash.
+    ///
+    /// Default implementation loads the whole code with [DatabaseRef::code_by_hash]. Databases
+    /// that can read a part of the code should override it.
+    fn code_prefix(&self, code_hash: B256, len: usize) -> Result<Bytes, Self::Error> {
+        let code = self.code_by_hash(code_hash)?.original_bytes();
+        Ok(code.slice(..len.min(code.len())))
+    }
+
+    /// Get storage value of address at index, with index and value as big-endian [B256].
+    fn storage_b256(&self, address: B160, index: B256) -> Result<B256, Self::Error> {
+        self.storage(address, index.into()).map(Into::into)
+    }
+
     // History related
     fn block_hash(&self, number: U256) -> Result<B256, Self::Error>;
 }
@@ -66,6 +94,10 @@ impl<'a, Error> Database for RefDBWrapper<'a, Error> {
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         self.db.code_by_hash(code_hash)
     }
+    /// Get at most `len` first bytes of the code with the hash.
+    fn code_prefix(&mut self, code_hash: B256, len: usize) -> Result<Bytes, Self::Error> {
+        self.db.code_prefix(code_hash, len)
+    }
     /// Get storage value of address at index.
     fn storage(&mut self, address: B160, index: U256) -> Result<U256, Self::Error> {
         self.db.storage(address, index)
@@ -76,3 +108,78 @@ impl<'a, Error> Database for RefDBWrapper<'a, Error> {
         self.db.block_hash(number)
     }
 }
+
+/// Database adapter for storage layouts that keep bytecode in a separate table keyed by code hash.
+///
+/// [AccountInfo] returned from `basic` is stripped of its `code`, only `code_hash` is kept.
+/// Bytecode is then fetched lazily with `code_by_hash`, and only when it is executed or read by
+/// one of the `EXTCODE*` instructions. Wrap this adapter in a caching database (for example
+/// `CacheDB`) to fetch each bytecode at most once per hash.
+#[derive(Clone, Debug, Default)]
+pub struct WrapDatabaseSplitCode<DB> {
+    pub db: DB,
+}
+
+impl<DB> WrapDatabaseSplitCode<DB> {
+    pub fn new(db: DB) -> Self {
+        Self { db }
+    }
+
+    /// Returns the wrapped database.
+    pub fn into_inner(self) -> DB {
+        self.db
+    }
+}
+
+fn strip_code(mut info: AccountInfo) -> AccountInfo {
+    info.code = None;
+    info
+}
+
+impl<DB: Database> Database for WrapDatabaseSplitCode<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: B160) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.db.basic(address)?.map(strip_code))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn code_prefix(&mut self, code_hash: B256, len: usize) -> Result<Bytes, Self::Error> {
+        self.db.code_prefix(code_hash, len)
+    }
+
+    fn storage(&mut self, address: B160, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for WrapDatabaseSplitCode<DB> {
+    type Error = DB::Error;
+
+    fn basic(&self, address: B160) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.db.basic(address)?.map(strip_code))
+    }
+
+    fn code_by_hash(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn code_prefix(&self, code_hash: B256, len: usize) -> Result<Bytes, Self::Error> {
+        self.db.code_prefix(code_hash, len)
+    }
+
+    fn storage(&self, address: B160, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    fn block_hash(&self, number: U256) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
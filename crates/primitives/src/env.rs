@@ -1,6 +1,8 @@
 use crate::{
-    alloc::vec::Vec, Account, EVMError, InvalidTransaction, Spec, SpecId, B160, B256, KECCAK_EMPTY,
-    MAX_INITCODE_SIZE, U256,
+    alloc::{vec, vec::Vec},
+    calc_blob_gasprice, calc_excess_blob_gas, Account, EVMError, EipOverrides, GasSchedule,
+    InvalidTransaction, Spec, SpecId, B160, B256, KECCAK_EMPTY, MAX_CODE_SIZE, SYSTEM_ADDRESS,
+    SYSTEM_CALL_GAS_LIMIT, U256,
 };
 use bytes::Bytes;
 use core::cmp::{min, Ordering};
@@ -28,6 +30,60 @@ pub struct BlockEnv {
     /// basefee is added in EIP1559 London upgrade
     pub basefee: U256,
     pub gas_limit: U256,
+    /// Excess blob gas and the blob gas price derived from it. Added in EIP-4844 Cancun upgrade.
+    ///
+    /// Price is computed once, when excess blob gas is set with
+    /// [BlockEnv::set_blob_excess_gas_and_price].
+    pub blob_excess_gas_and_price: Option<BlobExcessGasAndPrice>,
+}
+
+impl BlockEnv {
+    /// Sets excess blob gas of the block and computes the blob gas price.
+    pub fn set_blob_excess_gas_and_price(&mut self, excess_blob_gas: u64) {
+        self.blob_excess_gas_and_price = Some(BlobExcessGasAndPrice::new(excess_blob_gas));
+    }
+
+    /// Sets excess blob gas of the block from the parent header fields.
+    pub fn set_blob_excess_gas_from_parent(
+        &mut self,
+        parent_excess_blob_gas: u64,
+        parent_blob_gas_used: u64,
+    ) {
+        self.set_blob_excess_gas_and_price(calc_excess_blob_gas(
+            parent_excess_blob_gas,
+            parent_blob_gas_used,
+        ));
+    }
+
+    /// Returns excess blob gas of the block, `None` before Cancun.
+    pub fn get_blob_excess_gas(&self) -> Option<u64> {
+        self.blob_excess_gas_and_price
+            .map(|blob| blob.excess_blob_gas)
+    }
+
+    /// Returns blob gas price of the block, `None` before Cancun.
+    pub fn get_blob_gasprice(&self) -> Option<u128> {
+        self.blob_excess_gas_and_price
+            .map(|blob| blob.blob_gasprice)
+    }
+}
+
+/// Excess blob gas of the block together with the blob gas price computed from it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobExcessGasAndPrice {
+    pub excess_blob_gas: u64,
+    pub blob_gasprice: u128,
+}
+
+impl BlobExcessGasAndPrice {
+    /// Computes the blob gas price with [calc_blob_gasprice].
+    pub fn new(excess_blob_gas: u64) -> Self {
+        Self {
+            excess_blob_gas,
+            blob_gasprice: calc_blob_gasprice(excess_blob_gas),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -45,9 +101,17 @@ pub struct TxEnv {
     pub chain_id: Option<u64>,
     pub nonce: Option<u64>,
     pub access_list: Vec<(B160, Vec<U256>)>,
+    /// Accounts that are warm at the start of the transaction, on top of the coinbase and the
+    /// access list. Unlike the access list they are not charged intrinsic gas.
+    /// Useful to simulate a transaction in the middle of a block.
+    #[cfg(feature = "optional_warm_injection")]
+    pub warm_addresses: Vec<B160>,
+    /// Storage slots that are warm at the start of the transaction, not charged intrinsic gas.
+    #[cfg(feature = "optional_warm_injection")]
+    pub warm_storage: Vec<(B160, U256)>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransactTo {
     Call(B160),
@@ -87,8 +151,11 @@ pub struct CfgEnv {
     /// Default: Analyse
     pub perf_analyse_created_bytecodes: AnalysisKind,
     /// If some it will effects EIP-170: Contract code size limit. Usefull to increase this because of tests.
-    /// By default it is 0x6000 (~25kb).
+    /// By default it is 0x6000 (~25kb). Zero disables the limit.
     pub limit_contract_code_size: Option<usize>,
+    /// If some it will effects EIP-3860: Limit and meter initcode.
+    /// By default it is twice the contract code size limit. Zero disables the limit.
+    pub limit_initcode_size: Option<usize>,
     /// A hard memory limit in bytes beyond which [Memory] cannot be resized.
     ///
     /// In cases where the gas limit may be extraordinarily high, it is recommended to set this to
@@ -104,11 +171,22 @@ pub struct CfgEnv {
     /// By default, it is set to `false`.
     #[cfg(feature = "optional_block_gas_limit")]
     pub disable_block_gas_limit: bool,
+    /// Skips the EIP-170 contract code size and EIP-3860 initcode size checks, regardless of the
+    /// limits set. Initcode and code deposit gas is still charged. This is not consensus
+    /// behaviour, it is useful for simulating deployments of contracts that are over the limit.
+    /// By default, it is set to `false`.
+    #[cfg(feature = "optional_code_size_limit")]
+    pub disable_code_size_limit: bool,
     /// EIP-3607 rejects transactions from senders with deployed code. In development, it can be desirable to simulate
     /// calls from contracts, which this setting allows.
     /// By default, it is set to `false`.
     #[cfg(feature = "optional_eip3607")]
     pub disable_eip3607: bool,
+    /// Code prefixes that a sender can have without being rejected by EIP-3607, for example the
+    /// `0xef0100` delegation designator of EIP-7702. Code of the sender is loaded only if this is
+    /// not empty. It is not consulted when EIP-3607 is disabled.
+    /// By default, it is empty and senders with any code are rejected.
+    pub allowed_sender_code_prefixes: Vec<Bytes>,
     /// Disables all gas refunds. This is useful when using chains that have gas refunds disabled e.g. Avalanche.
     /// Reasoning behind removing gas refunds can be found in EIP-3298.
     /// By default, it is set to `false`.
@@ -118,9 +196,79 @@ pub struct CfgEnv {
     /// This is useful for testing method calls with zero gas price.
     #[cfg(feature = "optional_no_base_fee")]
     pub disable_base_fee: bool,
+    /// Maximum number of instructions executed by the transaction, including all subcalls.
+    /// Execution halts with [crate::Halt::InstructionLimitReached] once it is exhausted.
+    /// Useful as a deterministic execution budget for fuzzing.
+    /// By default, it is set to `None`.
+    #[cfg(feature = "optional_instruction_limit")]
+    pub instruction_limit: Option<u64>,
+    /// Record cold account and storage accesses charged by the interpreter.
+    /// They are returned in [crate::ResultAndState::cold_accesses].
+    /// By default, it is set to `false`.
+    pub record_cold_accesses: bool,
+    /// Distribution of the fee paid to the block beneficiary after the transaction.
+    ///
+    /// Gas fields of the execution result are not affected by it.
+    /// By default, the fee is paid to the block coinbase.
+    pub reward_policy: RewardPolicy,
+    /// Gas schedule that replaces the default gas costs of the spec.
+    ///
+    /// Intended for chains that reprice storage, memory or calldata. It is also used for the
+    /// refund cap applied at the end of the transaction.
+    /// By default, it is set to `None` and [GasSchedule::new] of `spec_id` is used.
+    pub gas_schedule: Option<GasSchedule>,
+    /// Overrides of single EIPs of the spec.
+    /// By default, no EIP is overridden.
+    pub eip_overrides: EipOverrides,
 }
 
 impl CfgEnv {
+    /// Sets the gas schedule that overrides the default costs of the spec.
+    pub fn with_gas_schedule(mut self, gas_schedule: GasSchedule) -> Self {
+        self.gas_schedule = Some(gas_schedule);
+        self
+    }
+
+    /// Returns the gas schedule used by the transaction.
+    pub fn gas_schedule(&self) -> GasSchedule {
+        self.gas_schedule
+            .unwrap_or_else(|| GasSchedule::new(self.spec_id))
+    }
+
+    /// Returns the EIP-170 contract code size limit.
+    pub fn max_code_size(&self) -> usize {
+        if self.is_code_size_limit_disabled() {
+            return usize::MAX;
+        }
+        match self.limit_contract_code_size {
+            Some(0) => usize::MAX,
+            Some(limit) => limit,
+            None => MAX_CODE_SIZE,
+        }
+    }
+
+    /// Returns the EIP-3860 initcode size limit.
+    pub fn max_initcode_size(&self) -> usize {
+        if self.is_code_size_limit_disabled() {
+            return usize::MAX;
+        }
+        match self.limit_initcode_size {
+            Some(0) => usize::MAX,
+            Some(limit) => limit,
+            None => self.max_code_size().saturating_mul(2),
+        }
+    }
+
+    #[cfg(feature = "optional_code_size_limit")]
+    pub fn is_code_size_limit_disabled(&self) -> bool {
+        self.disable_code_size_limit
+    }
+
+    #[cfg(not(feature = "optional_code_size_limit"))]
+    pub fn is_code_size_limit_disabled(&self) -> bool {
+        false
+    }
+
     #[cfg(feature = "optional_eip3607")]
     pub fn is_eip3607_disabled(&self) -> bool {
         self.disable_eip3607
@@ -131,6 +279,24 @@ impl CfgEnv {
         false
     }
 
+    /// Returns true if a sender with `code` is allowed by
+    /// [CfgEnv::allowed_sender_code_prefixes].
+    pub fn is_sender_code_allowed(&self, code: &[u8]) -> bool {
+        self.allowed_sender_code_prefixes
+            .iter()
+            .any(|prefix| code.starts_with(prefix))
+    }
+
+    /// Returns the length of the longest of [CfgEnv::allowed_sender_code_prefixes], bytes of
+    /// the sender code that are needed to check it.
+    pub fn max_sender_code_prefix_len(&self) -> usize {
+        self.allowed_sender_code_prefixes
+            .iter()
+            .map(Bytes::len)
+            .max()
+            .unwrap_or_default()
+    }
+
     #[cfg(feature = "optional_balance_check")]
     pub fn is_balance_check_disabled(&self) -> bool {
         self.disable_balance_check
@@ -181,6 +347,75 @@ pub enum AnalysisKind {
     Analyse,
 }
 
+/// Distribution of the transaction fee that is paid to the block beneficiary.
+///
+/// After EIP-1559 this is the priority fee, basefee is always burned.
+#[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RewardPolicy {
+    /// Fee is paid to the block coinbase.
+    #[default]
+    Coinbase,
+    /// Fee is paid to the given address.
+    Beneficiary(B160),
+    /// Fee is burned.
+    Burn,
+    /// Fee is split between addresses by their share in basis points (1/10000).
+    ///
+    /// There needs to be at least one address and shares can't add up to more than 10000.
+    /// Remainder that is not assigned because of rounding or shares adding up to less than
+    /// 10000 is paid to the first address.
+    Split(Vec<(B160, u16)>),
+}
+
+impl RewardPolicy {
+    /// Returns `false` if [RewardPolicy::Split] has no addresses or its shares add up to more
+    /// than 10000.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Split(shares) => {
+                !shares.is_empty()
+                    && shares.iter().map(|(_, bps)| u64::from(*bps)).sum::<u64>() <= 10_000
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns addresses and amounts that `reward` is paid to. Amounts add up to `reward`,
+    /// except for [RewardPolicy::Burn].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the policy is not valid, see [RewardPolicy::is_valid].
+    pub fn credits(&self, coinbase: B160, reward: U256) -> Vec<(B160, U256)> {
+        match self {
+            Self::Coinbase => vec![(coinbase, reward)],
+            Self::Beneficiary(beneficiary) => vec![(*beneficiary, reward)],
+            Self::Burn => Vec::new(),
+            Self::Split(shares) => {
+                assert!(self.is_valid(), "invalid reward split");
+                // split into quotient and remainder so the multiplication can't overflow.
+                let bps_total = U256::from(10_000);
+                let (quotient, remainder) = (reward / bps_total, reward % bps_total);
+                let mut credits: Vec<_> = shares
+                    .iter()
+                    .map(|(address, bps)| {
+                        let bps = U256::from(*bps);
+                        (*address, quotient * bps + remainder * bps / bps_total)
+                    })
+                    .collect();
+                let assigned = credits
+                    .iter()
+                    .fold(U256::ZERO, |sum, (_, amount)| sum + *amount);
+                if let Some((_, first)) = credits.first_mut() {
+                    *first += reward - assigned;
+                }
+                credits
+            }
+        }
+    }
+}
+
 impl Default for CfgEnv {
     fn default() -> CfgEnv {
         CfgEnv {
@@ -188,18 +423,28 @@ impl Default for CfgEnv {
             spec_id: SpecId::LATEST,
             perf_analyse_created_bytecodes: Default::default(),
             limit_contract_code_size: None,
+            limit_initcode_size: None,
             #[cfg(feature = "memory_limit")]
             memory_limit: 2u64.pow(32) - 1,
             #[cfg(feature = "optional_balance_check")]
             disable_balance_check: false,
             #[cfg(feature = "optional_block_gas_limit")]
             disable_block_gas_limit: false,
+            #[cfg(feature = "optional_code_size_limit")]
+            disable_code_size_limit: false,
             #[cfg(feature = "optional_eip3607")]
             disable_eip3607: false,
+            allowed_sender_code_prefixes: Vec::new(),
             #[cfg(feature = "optional_gas_refund")]
             disable_gas_refund: false,
             #[cfg(feature = "optional_no_base_fee")]
             disable_base_fee: false,
+            #[cfg(feature = "optional_instruction_limit")]
+            instruction_limit: None,
+            record_cold_accesses: false,
+            reward_policy: RewardPolicy::default(),
+            gas_schedule: None,
+            eip_overrides: EipOverrides::default(),
         }
     }
 }
@@ -214,6 +459,7 @@ impl Default for BlockEnv {
             difficulty: U256::ZERO,
             prevrandao: Some(B256::zero()),
             basefee: U256::ZERO,
+            blob_excess_gas_and_price: Some(BlobExcessGasAndPrice::new(0)),
         }
     }
 }
@@ -231,31 +477,101 @@ impl Default for TxEnv {
             chain_id: None,
             nonce: None,
             access_list: Vec::new(),
+            #[cfg(feature = "optional_warm_injection")]
+            warm_addresses: Vec::new(),
+            #[cfg(feature = "optional_warm_injection")]
+            warm_storage: Vec::new(),
+        }
+    }
+}
+
+impl TxEnv {
+    /// Returns transaction environment of a system call to `contract`.
+    ///
+    /// Caller is [SYSTEM_ADDRESS], gas limit is [SYSTEM_CALL_GAS_LIMIT] and gas price is zero.
+    pub fn new_system_call(contract: B160, data: Bytes) -> Self {
+        Self {
+            caller: SYSTEM_ADDRESS,
+            gas_limit: SYSTEM_CALL_GAS_LIMIT,
+            transact_to: TransactTo::Call(contract),
+            data,
+            ..Default::default()
+        }
+    }
+
+    /// Returns gas price paid by the transaction for the given block basefee.
+    ///
+    /// For EIP-1559 transactions this is `min(max_fee, basefee + priority_fee)`, for legacy
+    /// transactions it is the gas price.
+    pub fn effective_gas_price(&self, base_fee: U256) -> U256 {
+        match self.gas_priority_fee {
+            Some(priority_fee) => min(self.gas_price, base_fee.saturating_add(priority_fee)),
+            None => self.gas_price,
         }
     }
 }
 
+/// Transaction fee split, see [Env::calculate_fees].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeBreakdown {
+    /// Fee paid by the sender for the used gas.
+    pub paid_by_sender: U256,
+    /// Part of the fee credited according to [CfgEnv::reward_policy], by default to the coinbase.
+    pub to_coinbase: U256,
+    /// Part of the fee that is burned, basefee of the used gas after London.
+    pub burned: U256,
+    /// Prepaid fee of the unused gas that is returned to the sender.
+    pub refunded_to_sender: U256,
+}
+
 impl Env {
     pub fn effective_gas_price(&self) -> U256 {
-        if self.tx.gas_priority_fee.is_none() {
-            self.tx.gas_price
+        self.tx.effective_gas_price(self.block.basefee)
+    }
+
+    /// Returns fees of the transaction that used `gas_used` gas, after refunds, with the
+    /// hardfork of [CfgEnv::spec_id].
+    ///
+    /// This is what the EVM charges and credits when the transaction finishes.
+    pub fn calculate_fees(&self, gas_used: u64) -> FeeBreakdown {
+        self.calculate_fees_for_spec(self.cfg.spec_id, gas_used)
+    }
+
+    /// Same as [Env::calculate_fees] but with the given hardfork.
+    pub fn calculate_fees_for_spec(&self, spec_id: SpecId, gas_used: u64) -> FeeBreakdown {
+        let effective_gas_price = self.effective_gas_price();
+        let gas_used = U256::from(gas_used);
+        let paid_by_sender = effective_gas_price.saturating_mul(gas_used);
+        let refunded_to_sender = effective_gas_price
+            .saturating_mul(U256::from(self.tx.gas_limit).saturating_sub(gas_used));
+        // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
+        let coinbase_gas_price = if SpecId::enabled(spec_id, SpecId::LONDON) {
+            effective_gas_price.saturating_sub(self.block.basefee)
         } else {
-            min(
-                self.tx.gas_price,
-                self.block.basefee + self.tx.gas_priority_fee.unwrap(),
-            )
+            effective_gas_price
+        };
+        let to_coinbase = coinbase_gas_price.saturating_mul(gas_used);
+        FeeBreakdown {
+            paid_by_sender,
+            to_coinbase,
+            burned: paid_by_sender - to_coinbase,
+            refunded_to_sender,
         }
     }
 
     /// Validate ENV data of the block.
     ///
-    /// It can be skip if you are sure that PREVRANDAO is set.
+    /// It can be skip if you are sure that PREVRANDAO is set and the reward policy is valid.
     #[inline]
     pub fn validate_block_env<SPEC: Spec, T>(&self) -> Result<(), EVMError<T>> {
         // Prevrandao is required for merge
         if SPEC::enabled(SpecId::MERGE) && self.block.prevrandao.is_none() {
             return Err(EVMError::PrevrandaoNotSet);
         }
+        if !self.cfg.reward_policy.is_valid() {
+            return Err(EVMError::InvalidRewardPolicy);
+        }
         Ok(())
     }
 
@@ -290,7 +606,10 @@ impl Env {
         }
 
         // EIP-3860: Limit and meter initcode
-        if SPEC::enabled(SpecId::SHANGHAI) && is_create && self.tx.data.len() > MAX_INITCODE_SIZE {
+        if SPEC::enabled(SpecId::SHANGHAI)
+            && is_create
+            && self.tx.data.len() > self.cfg.max_initcode_size()
+        {
             return Err(InvalidTransaction::CreateInitcodeSizeLimit);
         }
 
@@ -310,12 +629,35 @@ impl Env {
     }
 
     /// Validate transaction agains state.
+    ///
+    /// Sender with code that is not loaded is rejected, see
+    /// [Env::validate_tx_agains_state_with_code_prefix].
     #[inline]
     pub fn validate_tx_agains_state(&self, account: &Account) -> Result<(), InvalidTransaction> {
+        self.validate_tx_agains_state_with_code_prefix(account, None)
+    }
+
+    /// Same as [Env::validate_tx_agains_state], with the beginning of the sender code that is
+    /// checked against [CfgEnv::allowed_sender_code_prefixes] if the code of the account is not
+    /// loaded. It needs to be [CfgEnv::max_sender_code_prefix_len] bytes long or the whole code.
+    #[inline]
+    pub fn validate_tx_agains_state_with_code_prefix(
+        &self,
+        account: &Account,
+        code_prefix: Option<&[u8]>,
+    ) -> Result<(), InvalidTransaction> {
+        let is_code_allowed = || match (&account.info.code, code_prefix) {
+            (Some(code), _) => self.cfg.is_sender_code_allowed(&code.original_bytes()),
+            (None, Some(prefix)) => self.cfg.is_sender_code_allowed(prefix),
+            (None, None) => false,
+        };
         // EIP-3607: Reject transactions from senders with deployed code
         // This EIP is introduced after london but there was no collision in past
         // so we can leave it enabled always
-        if !self.cfg.is_eip3607_disabled() && account.info.code_hash != KECCAK_EMPTY {
+        if !self.cfg.is_eip3607_disabled()
+            && account.info.code_hash != KECCAK_EMPTY
+            && !is_code_allowed()
+        {
             return Err(InvalidTransaction::RejectCallerWithCode);
         }
 
@@ -350,3 +692,233 @@ impl Env {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(gas_price: u64, priority_fee: Option<u64>, basefee: u64) -> Env {
+        let mut env = Env::default();
+        env.tx.gas_limit = 100_000;
+        env.tx.gas_price = U256::from(gas_price);
+        env.tx.gas_priority_fee = priority_fee.map(U256::from);
+        env.block.basefee = U256::from(basefee);
+        env
+    }
+
+    fn fees(paid: u64, to_coinbase: u64, burned: u64, refunded: u64) -> FeeBreakdown {
+        FeeBreakdown {
+            paid_by_sender: U256::from(paid),
+            to_coinbase: U256::from(to_coinbase),
+            burned: U256::from(burned),
+            refunded_to_sender: U256::from(refunded),
+        }
+    }
+
+    #[test]
+    fn effective_gas_price() {
+        let base_fee = U256::from(10);
+        // legacy
+        assert_eq!(
+            env(15, None, 0).tx.effective_gas_price(base_fee),
+            U256::from(15)
+        );
+        // priority fee is paid in full
+        assert_eq!(
+            env(15, Some(2), 0).tx.effective_gas_price(base_fee),
+            U256::from(12)
+        );
+        // capped by max fee
+        assert_eq!(
+            env(11, Some(2), 0).tx.effective_gas_price(base_fee),
+            U256::from(11)
+        );
+        assert_eq!(env(11, Some(2), 10).effective_gas_price(), U256::from(11));
+    }
+
+    #[test]
+    fn calculate_fees() {
+        // legacy transaction before London, whole fee goes to the coinbase.
+        let mut legacy = env(20, None, 0);
+        legacy.cfg.spec_id = SpecId::BERLIN;
+        assert_eq!(
+            legacy.calculate_fees(21_000),
+            fees(420_000, 420_000, 0, 1_580_000)
+        );
+
+        // legacy transaction after London pays the basefee too.
+        let legacy = env(20, None, 15);
+        assert_eq!(
+            legacy.calculate_fees(21_000),
+            fees(420_000, 105_000, 315_000, 1_580_000)
+        );
+
+        // EIP-1559 with priority fee.
+        let eip1559 = env(30, Some(2), 15);
+        assert_eq!(
+            eip1559.calculate_fees(50_000),
+            fees(850_000, 100_000, 750_000, 850_000)
+        );
+
+        // EIP-1559 capped by max fee.
+        let capped = env(16, Some(2), 15);
+        assert_eq!(
+            capped.calculate_fees(50_000),
+            fees(800_000, 50_000, 750_000, 800_000)
+        );
+
+        // zero basefee, nothing is burned.
+        let zero_basefee = env(30, Some(2), 0);
+        assert_eq!(
+            zero_basefee.calculate_fees(100_000),
+            fees(200_000, 200_000, 0, 0)
+        );
+    }
+
+    #[test]
+    fn calculate_fees_gwei_scale() {
+        // Gwei scale transactions with 1 gwei basefee.
+        const GWEI: u64 = 1_000_000_000;
+        let cases = [
+            // legacy transfer, gas price is the effective gas price.
+            (
+                (50 * GWEI, None, 21_000, 21_000),
+                50 * GWEI,
+                fees(
+                    1_050_000_000_000_000,
+                    1_029_000_000_000_000,
+                    21_000_000_000_000,
+                    0,
+                ),
+            ),
+            // EIP-1559 where max fee binds, coinbase gets less than the priority fee.
+            (
+                (1_200_000_000, Some(GWEI / 2), 60_000, 46_000),
+                1_200_000_000,
+                fees(
+                    55_200_000_000_000,
+                    9_200_000_000_000,
+                    46_000_000_000_000,
+                    16_800_000_000_000,
+                ),
+            ),
+            // EIP-1559 where priority fee binds.
+            (
+                (100 * GWEI, Some(2 * GWEI), 200_000, 120_000),
+                3 * GWEI,
+                fees(
+                    360_000_000_000_000,
+                    240_000_000_000_000,
+                    120_000_000_000_000,
+                    240_000_000_000_000,
+                ),
+            ),
+        ];
+        for ((gas_price, priority_fee, gas_limit, gas_used), effective_gas_price, expected) in cases
+        {
+            let mut env = env(gas_price, priority_fee, GWEI);
+            env.tx.gas_limit = gas_limit;
+            assert_eq!(env.effective_gas_price(), U256::from(effective_gas_price));
+
+            let fees = env.calculate_fees(gas_used);
+            assert_eq!(fees, expected);
+            assert_eq!(fees.burned, U256::from(gas_used * GWEI));
+            assert_eq!(fees.to_coinbase + fees.burned, fees.paid_by_sender);
+            assert_eq!(
+                fees.paid_by_sender + fees.refunded_to_sender,
+                U256::from(gas_limit) * U256::from(effective_gas_price)
+            );
+        }
+    }
+
+    #[test]
+    fn reward_policy_credits() {
+        let (coinbase, first, second) = (B160::from(0xc0), B160::from(0xa), B160::from(0xb));
+        let policies = [
+            RewardPolicy::Coinbase,
+            RewardPolicy::Beneficiary(first),
+            RewardPolicy::Split(vec![(first, 3333), (second, 6667)]),
+            RewardPolicy::Split(vec![(first, 1), (second, 1)]),
+            RewardPolicy::Split(vec![(first, 10_000)]),
+        ];
+        for policy in policies {
+            assert!(policy.is_valid());
+            for reward in [U256::ZERO, U256::from(21_000), U256::from(9_999), U256::MAX] {
+                let credits = policy.credits(coinbase, reward);
+                let total = credits
+                    .iter()
+                    .fold(U256::ZERO, |sum, (_, amount)| sum + *amount);
+                assert_eq!(total, reward, "{policy:?} {reward}");
+            }
+        }
+        assert!(RewardPolicy::Burn
+            .credits(coinbase, U256::from(1))
+            .is_empty());
+
+        let invalid = [
+            RewardPolicy::Split(vec![]),
+            RewardPolicy::Split(vec![(first, u16::MAX)]),
+            RewardPolicy::Split(vec![(first, 6_000), (second, 5_000)]),
+        ];
+        for policy in invalid {
+            assert!(!policy.is_valid());
+            let env = Env {
+                cfg: CfgEnv {
+                    reward_policy: policy,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            assert_eq!(
+                env.validate_block_env::<crate::LatestSpec, ()>(),
+                Err(EVMError::InvalidRewardPolicy)
+            );
+        }
+    }
+
+    #[test]
+    fn sender_code_prefixes() {
+        use crate::{AccountInfo, Bytecode};
+
+        let sender = |code: &'static [u8]| {
+            Account::from(AccountInfo::new(
+                U256::ZERO,
+                0,
+                Bytecode::new_raw(Bytes::from_static(code)),
+            ))
+        };
+        let mut env = Env::default();
+        env.tx.gas_limit = 0;
+        let delegated = sender(&[0xef, 0x01, 0x00, 0xaa]);
+        let contract = sender(&[0x60, 0x00]);
+
+        assert_eq!(
+            env.validate_tx_agains_state(&delegated),
+            Err(InvalidTransaction::RejectCallerWithCode)
+        );
+        env.cfg.allowed_sender_code_prefixes = vec![Bytes::from_static(&[0xef, 0x01, 0x00])];
+        assert_eq!(env.validate_tx_agains_state(&delegated), Ok(()));
+        assert_eq!(
+            env.validate_tx_agains_state(&contract),
+            Err(InvalidTransaction::RejectCallerWithCode)
+        );
+        assert_eq!(env.validate_tx_agains_state(&sender(&[])), Ok(()));
+
+        // code that is not loaded is checked by its prefix.
+        let mut unloaded = delegated;
+        unloaded.info.code = None;
+        assert_eq!(
+            env.validate_tx_agains_state(&unloaded),
+            Err(InvalidTransaction::RejectCallerWithCode)
+        );
+        assert_eq!(env.cfg.max_sender_code_prefix_len(), 3);
+        assert_eq!(
+            env.validate_tx_agains_state_with_code_prefix(&unloaded, Some(&[0xef, 0x01, 0x00])),
+            Ok(())
+        );
+        assert_eq!(
+            env.validate_tx_agains_state_with_code_prefix(&unloaded, Some(&[0x60, 0x00])),
+            Err(InvalidTransaction::RejectCallerWithCode)
+        );
+    }
+}
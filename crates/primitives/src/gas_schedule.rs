@@ -0,0 +1,73 @@
+use crate::SpecId::{self, *};
+
+/// Gas costs that can be changed by chains that reprice a subset of the opcodes.
+///
+/// Default schedule of each spec is available as [crate::Spec::GAS_SCHEDULE] and is known at
+/// compile time. Custom schedule can be set with [crate::CfgEnv::with_gas_schedule].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GasSchedule {
+    /// Cost of `SLOAD` of cold slot, or of any slot before Berlin.
+    pub cold_sload_cost: u64,
+    /// Cost of `SLOAD` of warm slot, or of any slot before Berlin.
+    pub warm_storage_read_cost: u64,
+    /// Cost of setting zero slot to non zero value.
+    pub sstore_set: u64,
+    /// Cost of changing non zero slot. After Berlin cold access cost is included in it.
+    pub sstore_reset: u64,
+    /// Refund for clearing a slot.
+    pub sstore_clears_refund: u64,
+    /// Gas given to the callee on top of the gas limit if call transfers value.
+    pub call_stipend: u64,
+    /// Linear cost of memory expansion per word.
+    pub memory_word: u64,
+    /// Cost of zero byte of transaction data.
+    pub calldata_zero_byte: u64,
+    /// Cost of non zero byte of transaction data.
+    pub calldata_nonzero_byte: u64,
+    /// Refund is capped to `gas_used / max_refund_quotient`. Zero disables refunds.
+    pub max_refund_quotient: u64,
+}
+
+impl GasSchedule {
+    /// Returns gas schedule of the given spec.
+    pub const fn new(spec_id: SpecId) -> Self {
+        let (cold_sload_cost, warm_storage_read_cost) = if SpecId::enabled(spec_id, BERLIN) {
+            (2100, 100)
+        } else if SpecId::enabled(spec_id, ISTANBUL) {
+            // EIP-1884: Repricing for trie-size-dependent opcodes
+            (800, 800)
+        } else if SpecId::enabled(spec_id, TANGERINE) {
+            // EIP-150: Gas cost changes for IO-heavy operations
+            (200, 200)
+        } else {
+            (50, 50)
+        };
+        let london = SpecId::enabled(spec_id, LONDON);
+        Self {
+            cold_sload_cost,
+            warm_storage_read_cost,
+            sstore_set: 20000,
+            sstore_reset: 5000,
+            // EIP-3529: Reduction in refunds
+            sstore_clears_refund: if london { 4800 } else { 15000 },
+            call_stipend: 2300,
+            memory_word: 3,
+            calldata_zero_byte: 4,
+            // EIP-2028: Transaction data gas cost reduction
+            calldata_nonzero_byte: if SpecId::enabled(spec_id, ISTANBUL) {
+                16
+            } else {
+                68
+            },
+            // EIP-3529: Reduction in refunds
+            max_refund_quotient: if london { 5 } else { 2 },
+        }
+    }
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self::new(LATEST)
+    }
+}
@@ -2,12 +2,15 @@
 
 pub mod bits;
 pub mod bytecode;
+pub mod compat;
 pub mod constants;
 pub mod db;
 pub mod env;
+pub mod gas_schedule;
 pub mod log;
 pub mod precompile;
 pub mod result;
+pub mod revert;
 pub mod specification;
 pub mod state;
 pub mod utilities;
@@ -27,12 +30,15 @@ pub type Hash = B256;
 
 pub use bitvec;
 pub use bytecode::*;
+pub use compat::CompatError;
 pub use constants::*;
 pub use env::*;
+pub use gas_schedule::GasSchedule;
 pub use hashbrown::{hash_map, hash_set, HashMap, HashSet};
 pub use log::Log;
 pub use precompile::*;
 pub use result::*;
+pub use revert::{PanicKind, RevertReason};
 pub use ruint;
 pub use ruint::aliases::U256;
 pub use ruint::uint;
@@ -1,4 +1,4 @@
-use crate::{Log, State, B160};
+use crate::{Log, RevertReason, State, B160, B256};
 use alloc::vec::Vec;
 use bytes::Bytes;
 use core::fmt;
@@ -13,6 +13,65 @@ pub struct ResultAndState {
     pub result: ExecutionResult,
     /// State that got updated
     pub state: State,
+    /// Contracts deployed by the transaction, in order of creation.
+    ///
+    /// Creations that were reverted are not included.
+    pub created_contracts: Vec<CreatedContract>,
+    /// Balance transfers done by `SELFDESTRUCT`, in order of execution.
+    ///
+    /// Selfdestructs in reverted frames are not included.
+    pub selfdestructs: Vec<SelfdestructEvent>,
+    /// EIP-2929 cold accesses charged during execution, in order of execution.
+    ///
+    /// Recorded only if [crate::CfgEnv::record_cold_accesses] is set. Accesses of reverted
+    /// frames are included as their gas was charged.
+    pub cold_accesses: Vec<ColdAccess>,
+}
+
+/// Contract deployed by top level create or by `CREATE`/`CREATE2` opcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreatedContract {
+    /// Address of the created contract.
+    pub address: B160,
+    /// Account that executed the create.
+    pub deployer: B160,
+    /// Keccak hash of init code.
+    pub init_code_hash: B256,
+    /// Hash of deployed bytecode.
+    pub code_hash: B256,
+    /// True if contract selfdestructed in the same transaction.
+    pub destroyed: bool,
+}
+
+/// EIP-2929 cold account or storage access.
+///
+/// Precompiles and addresses or slots from the access list are warm and never recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColdAccess {
+    /// Accessed account.
+    pub address: B160,
+    /// Accessed storage slot, `None` for account access.
+    pub slot: Option<U256>,
+    /// Opcode that did the access.
+    pub opcode: u8,
+    /// Cold access cost included in the charge of the opcode.
+    /// It is `2600` for account access and the cold SLOAD cost of the gas schedule, `2100` by
+    /// default, for storage access.
+    pub gas_charged: u64,
+}
+
+/// Balance transfer done by `SELFDESTRUCT` opcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfdestructEvent {
+    /// Address of the destroyed contract.
+    pub contract: B160,
+    /// Address that received the balance.
+    pub beneficiary: B160,
+    /// Transferred balance. If contract is its own beneficiary, this balance is burned.
+    pub value: U256,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -82,6 +141,16 @@ impl ExecutionResult {
         }
     }
 
+    /// Decodes the output of a reverted execution.
+    ///
+    /// Returns `None` if execution was not reverted or reverted without output.
+    pub fn revert_reason(&self) -> Option<RevertReason> {
+        match self {
+            Self::Revert { output, .. } => RevertReason::decode(output),
+            _ => None,
+        }
+    }
+
     pub fn gas_used(&self) -> u64 {
         let (Self::Success { gas_used, .. }
         | Self::Revert { gas_used, .. }
@@ -126,6 +195,12 @@ pub enum EVMError<DBError> {
     Transaction(InvalidTransaction),
     /// REVM specific and related to environment.
     PrevrandaoNotSet,
+    /// [crate::RewardPolicy::Split] has no addresses or its shares add up to more than 10000
+    /// basis points.
+    InvalidRewardPolicy,
+    /// Transaction was verified under different spec, environment or caller nonce than the
+    /// ones it is executed with.
+    VerifiedTxMismatch,
     Database(DBError),
 }
 
@@ -140,6 +215,8 @@ where
         match self {
             EVMError::Transaction(v) => write!(f, "Transaction error: {:?}", v),
             EVMError::PrevrandaoNotSet => f.write_str("Prevrandao not set"),
+            EVMError::InvalidRewardPolicy => f.write_str("Invalid reward split"),
+            EVMError::VerifiedTxMismatch => f.write_str("Verified transaction does not match"),
             EVMError::Database(v) => write!(f, "Database error: {}", v),
         }
     }
@@ -216,6 +293,8 @@ pub enum Halt {
     CreateContractStartingWithEF,
     /// EIP-3860: Limit and meter initcode. Initcode size limit exceeded.
     CreateInitcodeSizeLimit,
+    /// Instruction limit set in [crate::CfgEnv] is exhausted.
+    InstructionLimitReached,
 
     /* Internal Halts that can be only found inside Inspector */
     OverflowPayment,
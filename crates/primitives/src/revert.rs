@@ -0,0 +1,252 @@
+//! Decoding of the standard Solidity revert payloads.
+
+use crate::U256;
+use alloc::string::String;
+use bytes::Bytes;
+
+/// Selector of `Error(string)`.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decoded revert output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RevertReason {
+    /// `Error(string)` emitted by `require` and `revert` with a message.
+    Message(String),
+    /// `Panic(uint256)` emitted by failed asserts and checked operations.
+    Panic(PanicKind),
+    /// Custom error, or one of the standard errors with a malformed payload.
+    Custom {
+        selector: [u8; 4],
+        /// ABI encoded arguments that follow the selector.
+        #[cfg_attr(feature = "serde", serde(with = "crate::utilities::serde_hex_bytes"))]
+        data: Bytes,
+    },
+    /// Output that is too short to contain a selector.
+    Raw(#[cfg_attr(feature = "serde", serde(with = "crate::utilities::serde_hex_bytes"))] Bytes),
+}
+
+impl RevertReason {
+    /// Decodes revert output. Returns `None` for an empty output.
+    ///
+    /// `Error(string)` and `Panic(uint256)` payloads that can't be decoded are returned as
+    /// [RevertReason::Custom].
+    pub fn decode(output: &Bytes) -> Option<Self> {
+        if output.is_empty() {
+            return None;
+        }
+        if output.len() < 4 {
+            return Some(Self::Raw(output.clone()));
+        }
+        let selector = [output[0], output[1], output[2], output[3]];
+        let data = &output[4..];
+        let decoded = match selector {
+            ERROR_SELECTOR => decode_string(data).map(Self::Message),
+            PANIC_SELECTOR => {
+                read_word(data, 0).map(|code| Self::Panic(PanicKind::from_code(code)))
+            }
+            _ => None,
+        };
+        Some(decoded.unwrap_or_else(|| Self::Custom {
+            selector,
+            data: output.slice(4..),
+        }))
+    }
+}
+
+/// Panic codes of `Panic(uint256)` as defined by Solidity.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PanicKind {
+    /// 0x00: generic compiler inserted panic.
+    Generic,
+    /// 0x01: `assert` with a false condition.
+    Assert,
+    /// 0x11: arithmetic overflow or underflow outside of an `unchecked` block.
+    ArithmeticOverflow,
+    /// 0x12: division or modulo by zero.
+    DivisionByZero,
+    /// 0x21: conversion of an out of range value to an enum.
+    InvalidEnumValue,
+    /// 0x22: access to an incorrectly encoded storage byte array.
+    InvalidStorageByteArray,
+    /// 0x31: `pop` on an empty array.
+    EmptyArrayPop,
+    /// 0x32: array or slice index out of bounds.
+    ArrayOutOfBounds,
+    /// 0x41: too much memory allocated or too large array created.
+    OutOfMemory,
+    /// 0x51: call of a zero initialized internal function variable.
+    UninitializedFunctionPointer,
+    /// Code that is not known.
+    Unknown(U256),
+}
+
+impl PanicKind {
+    /// Maps panic code to its kind.
+    pub fn from_code(code: U256) -> Self {
+        let small = match u8::try_from(code) {
+            Ok(small) => small,
+            Err(_) => return Self::Unknown(code),
+        };
+        match small {
+            0x00 => Self::Generic,
+            0x01 => Self::Assert,
+            0x11 => Self::ArithmeticOverflow,
+            0x12 => Self::DivisionByZero,
+            0x21 => Self::InvalidEnumValue,
+            0x22 => Self::InvalidStorageByteArray,
+            0x31 => Self::EmptyArrayPop,
+            0x32 => Self::ArrayOutOfBounds,
+            0x41 => Self::OutOfMemory,
+            0x51 => Self::UninitializedFunctionPointer,
+            _ => Self::Unknown(code),
+        }
+    }
+
+    /// Returns the panic code.
+    pub fn code(&self) -> U256 {
+        let code: u8 = match self {
+            Self::Generic => 0x00,
+            Self::Assert => 0x01,
+            Self::ArithmeticOverflow => 0x11,
+            Self::DivisionByZero => 0x12,
+            Self::InvalidEnumValue => 0x21,
+            Self::InvalidStorageByteArray => 0x22,
+            Self::EmptyArrayPop => 0x31,
+            Self::ArrayOutOfBounds => 0x32,
+            Self::OutOfMemory => 0x41,
+            Self::UninitializedFunctionPointer => 0x51,
+            Self::Unknown(code) => return *code,
+        };
+        U256::from(code)
+    }
+}
+
+/// Reads 32 byte word at `offset`, `None` if it is out of bounds.
+fn read_word(data: &[u8], offset: usize) -> Option<U256> {
+    let word = data.get(offset..offset.checked_add(32)?)?;
+    Some(U256::from_be_bytes::<32>(word.try_into().ok()?))
+}
+
+/// Decodes ABI encoded `string` that is the only argument.
+fn decode_string(data: &[u8]) -> Option<String> {
+    let offset = usize::try_from(read_word(data, 0)?).ok()?;
+    let len = usize::try_from(read_word(data, offset)?).ok()?;
+    let start = offset + 32;
+    let bytes = data.get(start..start.checked_add(len)?)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn message() {
+        // UniswapV2Router02 deadline check.
+        let output = Bytes::from_static(&hex!(
+            "08c379a0"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "0000000000000000000000000000000000000000000000000000000000000018"
+            "556e69737761705632526f757465723a20455850495245440000000000000000"
+        ));
+        assert_eq!(
+            RevertReason::decode(&output),
+            Some(RevertReason::Message("UniswapV2Router: EXPIRED".into()))
+        );
+    }
+
+    #[test]
+    fn truncated_message() {
+        // OpenZeppelin Ownable revert cut in the middle of the message.
+        let output = Bytes::from_static(&hex!(
+            "08c379a0"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "4f776e61626c653a2063616c6c6572206973"
+        ));
+        assert_eq!(
+            RevertReason::decode(&output),
+            Some(RevertReason::Custom {
+                selector: ERROR_SELECTOR,
+                data: output.slice(4..),
+            })
+        );
+
+        // offset pointing past the payload.
+        let output = Bytes::from_static(&hex!(
+            "08c379a0"
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+        ));
+        assert!(matches!(
+            RevertReason::decode(&output),
+            Some(RevertReason::Custom {
+                selector: ERROR_SELECTOR,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn panic() {
+        let output = Bytes::from_static(&hex!(
+            "4e487b71"
+            "0000000000000000000000000000000000000000000000000000000000000011"
+        ));
+        assert_eq!(
+            RevertReason::decode(&output),
+            Some(RevertReason::Panic(PanicKind::ArithmeticOverflow))
+        );
+
+        let output = Bytes::from_static(&hex!(
+            "4e487b71"
+            "0000000000000000000000000000000000000000000000000000000000000100"
+        ));
+        assert_eq!(
+            RevertReason::decode(&output),
+            Some(RevertReason::Panic(PanicKind::Unknown(U256::from(0x100))))
+        );
+
+        let output = Bytes::from_static(&hex!("4e487b7100"));
+        assert!(matches!(
+            RevertReason::decode(&output),
+            Some(RevertReason::Custom {
+                selector: PANIC_SELECTOR,
+                ..
+            })
+        ));
+
+        for code in [0x00, 0x01, 0x12, 0x21, 0x22, 0x31, 0x32, 0x41, 0x51, 0x99] {
+            let code = U256::from(code);
+            assert_eq!(PanicKind::from_code(code).code(), code);
+        }
+    }
+
+    #[test]
+    fn custom_and_raw() {
+        // ERC20InsufficientBalance(address,uint256,uint256)
+        let output = Bytes::from_static(&hex!(
+            "e450d38c"
+            "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+            "0000000000000000000000000000000000000000000000000000000000000001"
+            "0000000000000000000000000000000000000000000000000000000000000002"
+        ));
+        assert_eq!(
+            RevertReason::decode(&output),
+            Some(RevertReason::Custom {
+                selector: hex!("e450d38c"),
+                data: output.slice(4..),
+            })
+        );
+
+        assert_eq!(
+            RevertReason::decode(&Bytes::from_static(&[0xfe])),
+            Some(RevertReason::Raw(Bytes::from_static(&[0xfe])))
+        );
+        assert_eq!(RevertReason::decode(&Bytes::new()), None);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::GasSchedule;
+
 /// SpecId and their activation block
 /// Information was obtained from: https://github.com/ethereum/execution-specs
 #[repr(u8)]
@@ -63,12 +65,42 @@ impl SpecId {
     }
 }
 
+/// Overrides of single EIPs that are otherwise enabled together with a spec.
+///
+/// Useful for chains that activate features of a hardfork one by one. `None` keeps the
+/// behavior of the spec.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EipOverrides {
+    /// EIP-3855: PUSH0 instruction, enabled in Shanghai.
+    pub eip3855_push0: Option<bool>,
+}
+
+impl EipOverrides {
+    /// Returns true if PUSH0 is enabled in the given spec.
+    #[inline]
+    pub const fn is_push0_enabled(&self, spec_id: SpecId) -> bool {
+        Self::resolve(self.eip3855_push0, spec_id, SpecId::SHANGHAI)
+    }
+
+    /// Returns the override if it is set, otherwise if `activation` is enabled in `spec_id`.
+    #[inline]
+    const fn resolve(flag: Option<bool>, spec_id: SpecId, activation: SpecId) -> bool {
+        match flag {
+            Some(enabled) => enabled,
+            None => SpecId::enabled(spec_id, activation),
+        }
+    }
+}
+
 pub trait Spec: Sized {
     #[inline(always)]
     fn enabled(spec_id: SpecId) -> bool {
         Self::SPEC_ID as u8 >= spec_id as u8
     }
     const SPEC_ID: SpecId;
+    /// Default gas schedule of the spec.
+    const GAS_SCHEDULE: GasSchedule = GasSchedule::new(Self::SPEC_ID);
 }
 
 macro_rules! spec {
@@ -192,9 +192,9 @@ impl AccountInfo {
         }
     }
 
+    /// Is account empty as defined by EIP-161. See [is_eip161_empty].
     pub fn is_empty(&self) -> bool {
-        let code_empty = self.code_hash == KECCAK_EMPTY || self.code_hash == B256::zero();
-        self.balance == U256::ZERO && self.nonce == 0 && code_empty
+        is_eip161_empty(self)
     }
 
     pub fn exists(&self) -> bool {
@@ -209,9 +209,21 @@ impl AccountInfo {
     }
 }
 
+/// Account is empty as defined by [EIP-161](https://eips.ethereum.org/EIPS/eip-161):
+/// it has zero nonce, zero balance and no code.
+///
+/// Code is considered empty if code hash is [KECCAK_EMPTY] or zero, zero hash is used by
+/// databases that don't set the hash for accounts without code.
+/// Loaded bytecode is not inspected, only the hash is.
+pub fn is_eip161_empty(info: &AccountInfo) -> bool {
+    let code_empty = info.code_hash == KECCAK_EMPTY || info.code_hash == B256::zero();
+    info.balance == U256::ZERO && info.nonce == 0 && code_empty
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Account;
+    use super::is_eip161_empty;
+    use crate::{Account, AccountInfo, Bytecode, Bytes, B256, KECCAK_EMPTY, U256};
 
     #[test]
     pub fn account_state() {
@@ -232,4 +244,32 @@ mod tests {
         assert!(account.is_touched());
         assert!(!account.is_selfdestructed());
     }
+
+    #[test]
+    pub fn eip161_empty() {
+        let empty = AccountInfo::default();
+        assert!(is_eip161_empty(&empty));
+        assert!(is_eip161_empty(&AccountInfo {
+            code_hash: B256::zero(),
+            code: None,
+            ..Default::default()
+        }));
+
+        assert!(!is_eip161_empty(&AccountInfo {
+            nonce: 1,
+            ..Default::default()
+        }));
+        assert!(!is_eip161_empty(&AccountInfo::from_balance(U256::from(1))));
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x00]));
+        assert!(!is_eip161_empty(&AccountInfo::new(U256::ZERO, 0, code)));
+        // only the code hash is checked, code does not need to be loaded.
+        assert!(!is_eip161_empty(&AccountInfo {
+            code_hash: B256::repeat_byte(1),
+            code: None,
+            ..Default::default()
+        }));
+
+        assert_eq!(empty.code_hash, KECCAK_EMPTY);
+        assert_eq!(empty.is_empty(), is_eip161_empty(&empty));
+    }
 }
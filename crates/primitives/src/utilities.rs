@@ -1,11 +1,20 @@
-use crate::{B160, B256, U256};
+use crate::{
+    B160, B256, BLOB_GASPRICE_UPDATE_FRACTION, MIN_BLOB_GASPRICE, TARGET_BLOB_GAS_PER_BLOCK, U256,
+};
 use hex_literal::hex;
+#[cfg(feature = "asm-keccak")]
+use keccak_asm::{Digest, Keccak256};
+#[cfg(not(feature = "asm-keccak"))]
 use sha3::{Digest, Keccak256};
 
 pub const KECCAK_EMPTY: B256 = B256(hex!(
     "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
 ));
 
+/// Computes keccak256 hash of the input.
+///
+/// With the `asm-keccak` feature the assembly implementation from `keccak-asm` is used,
+/// otherwise the portable one from `sha3`. Both produce the same output.
 #[inline(always)]
 pub fn keccak256(input: &[u8]) -> B256 {
     B256(Keccak256::digest(input)[..].try_into().unwrap())
@@ -31,6 +40,60 @@ pub fn create2_address(caller: B160, code_hash: B256, salt: U256) -> B160 {
     B160(hasher.finalize().as_slice()[12..].try_into().unwrap())
 }
 
+/// Calculates the `excess_blob_gas` of a block from the parent header fields.
+///
+/// See also [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844) (`calc_excess_blob_gas`).
+#[inline]
+pub fn calc_excess_blob_gas(parent_excess_blob_gas: u64, parent_blob_gas_used: u64) -> u64 {
+    parent_excess_blob_gas
+        .saturating_add(parent_blob_gas_used)
+        .saturating_sub(TARGET_BLOB_GAS_PER_BLOCK)
+}
+
+/// Calculates the blob gas price from the block's `excess_blob_gas`.
+///
+/// See also [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844) (`get_blob_gasprice`).
+#[inline]
+pub fn calc_blob_gasprice(excess_blob_gas: u64) -> u128 {
+    fake_exponential(
+        MIN_BLOB_GASPRICE,
+        excess_blob_gas,
+        BLOB_GASPRICE_UPDATE_FRACTION,
+    )
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using Taylor expansion.
+///
+/// Result is exact integer arithmetic of the EIP-4844 reference implementation. The reference
+/// uses unbounded integers, here the terms are accumulated in [U256] and the result saturates at
+/// `u128::MAX`. Terms can only overflow [U256] once the result is above `u128::MAX`.
+///
+/// # Panics
+///
+/// Panics if `denominator` is zero.
+pub fn fake_exponential(factor: u64, numerator: u64, denominator: u64) -> u128 {
+    assert_ne!(denominator, 0, "attempt to divide by zero");
+    let factor = U256::from(factor);
+    let numerator = U256::from(numerator);
+    let denominator = U256::from(denominator);
+
+    let mut i = U256::from(1);
+    let mut output = U256::ZERO;
+    let mut numerator_accum = factor * denominator;
+    while numerator_accum > U256::ZERO {
+        output = match output.checked_add(numerator_accum) {
+            Some(output) => output,
+            None => return u128::MAX,
+        };
+        numerator_accum = match numerator_accum.checked_mul(numerator) {
+            Some(accum) => accum / (denominator * i),
+            None => return u128::MAX,
+        };
+        i += U256::from(1);
+    }
+    u128::try_from(output / denominator).unwrap_or(u128::MAX)
+}
+
 /// Serde functions to serde as [bytes::Bytes] hex string
 #[cfg(feature = "serde")]
 pub mod serde_hex_bytes {
@@ -59,3 +122,108 @@ pub mod serde_hex_bytes {
         .map_err(|e| serde::de::Error::custom(e.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keccak256_matches_reference() {
+        use sha3::{Digest, Keccak256};
+
+        assert_eq!(keccak256(&[]), KECCAK_EMPTY);
+        for len in [1, 31, 32, 33, 135, 136, 137, 1000] {
+            let input = (0..len).map(|i| i as u8).collect::<alloc::vec::Vec<_>>();
+            let expected = B256(Keccak256::digest(&input)[..].try_into().unwrap());
+            assert_eq!(keccak256(&input), expected, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_calc_excess_blob_gas() {
+        use crate::GAS_PER_BLOB;
+
+        for (parent_excess, parent_used, expected) in [
+            (0, 0, 0),
+            (0, TARGET_BLOB_GAS_PER_BLOCK, 0),
+            (0, TARGET_BLOB_GAS_PER_BLOCK + GAS_PER_BLOB, GAS_PER_BLOB),
+            (
+                1,
+                TARGET_BLOB_GAS_PER_BLOCK + GAS_PER_BLOB,
+                GAS_PER_BLOB + 1,
+            ),
+            (TARGET_BLOB_GAS_PER_BLOCK, 0, 0),
+            (TARGET_BLOB_GAS_PER_BLOCK + 1, 0, 1),
+            (u64::MAX, u64::MAX, u64::MAX - TARGET_BLOB_GAS_PER_BLOCK),
+        ] {
+            assert_eq!(
+                calc_excess_blob_gas(parent_excess, parent_used),
+                expected,
+                "{} {}",
+                parent_excess,
+                parent_used
+            );
+        }
+    }
+
+    #[test]
+    fn test_calc_blob_gasprice() {
+        for (excess, expected) in [
+            (0, 1),
+            (2314057, 1),
+            (2314058, 2),
+            (10 * 1024 * 1024, 23),
+            (192204553, 10079296854086811361005191),
+        ] {
+            assert_eq!(calc_blob_gasprice(excess), expected, "{}", excess);
+        }
+        assert_eq!(calc_blob_gasprice(u64::MAX), u128::MAX);
+    }
+
+    #[test]
+    fn test_fake_exponential() {
+        for (factor, numerator, denominator, expected) in [
+            (1, 0, 1, 1),
+            (38493, 0, 1000, 38493),
+            (0, 1234, 2345, 0),
+            (1, 2, 1, 6), // approximate 7.389
+            (1, 4, 2, 6),
+            (1, 3, 1, 16), // approximate 20.09
+            (1, 6, 2, 18),
+            (1, 4, 1, 49), // approximate 54.60
+            (1, 8, 2, 50),
+            (10, 8, 2, 542), // approximate 540.598
+            (11, 8, 2, 596), // approximate 600.58
+            (1, 5, 1, 136),  // approximate 148.4
+            (1, 5, 2, 11),   // approximate 12.18
+            (2, 5, 2, 23),   // approximate 24.36
+            (1, 50000000, 2225652, 5709098764),
+            // ~489 blocks with all blobs above the target, terms overflow u128.
+            (1, 192204553, 3338477, 10079296854086811361005191),
+            (
+                1,
+                296000000,
+                3338477,
+                320576357588920898972531695686736004827,
+            ),
+            (1, 297000000, 3338477, u128::MAX),
+        ] {
+            assert_eq!(
+                fake_exponential(factor, numerator, denominator),
+                expected,
+                "{} {} {}",
+                factor,
+                numerator,
+                denominator
+            );
+        }
+    }
+
+    #[test]
+    fn blob_gasprice_is_monotonic() {
+        proptest::proptest!(|(a: u64, b: u64)| {
+            let (low, high) = if a <= b { (a, b) } else { (b, a) };
+            proptest::prop_assert!(calc_blob_gasprice(low) <= calc_blob_gasprice(high));
+        });
+    }
+}
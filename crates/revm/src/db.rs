@@ -1,4 +1,5 @@
 pub mod in_memory_db;
+pub mod metrics;
 
 #[cfg(feature = "ethersdb")]
 pub mod ethersdb;
@@ -12,3 +13,4 @@ compile_error!(
 
 pub use crate::primitives::db::*;
 pub use in_memory_db::*;
+pub use metrics::{AtomicMetrics, MetricsSnapshot};
@@ -1,11 +1,11 @@
-use super::{DatabaseCommit, DatabaseRef};
+use super::{AtomicMetrics, DatabaseCommit, DatabaseRef};
 use crate::primitives::{
-    hash_map::Entry, keccak256, Account, AccountInfo, Bytecode, HashMap, Log, B160, B256,
-    KECCAK_EMPTY, U256,
+    hash_map::Entry, keccak256, Account, AccountInfo, Bytecode, Bytes, HashMap, HashSet, Log, B160,
+    B256, BLOCK_HASH_HISTORY, KECCAK_EMPTY, U256,
 };
 use crate::Database;
-use alloc::vec::Vec;
-use core::convert::Infallible;
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::{convert::Infallible, sync::atomic::AtomicU64};
 
 pub type InMemoryDB = CacheDB<EmptyDB>;
 
@@ -28,15 +28,29 @@ pub struct CacheDB<ExtDB: DatabaseRef> {
     /// `code` is always `None`, and bytecode can be found in `contracts`.
     pub accounts: HashMap<B160, DbAccount>,
     /// Tracks all contracts by their code hash.
+    ///
+    /// Accounts sharing the same code share one entry, and clones handed out from it share
+    /// its allocations.
     pub contracts: HashMap<B256, Bytecode>,
     /// All logs that were committed via [DatabaseCommit::commit].
     pub logs: Vec<Log>,
-    /// All cached block hashes from the [DatabaseRef].
-    pub block_hashes: HashMap<U256, B256>,
+    /// Cached block hashes from the [DatabaseRef] or set with [CacheDB::set_block_hash].
+    ///
+    /// At most `block_hash_limit` entries are retained, see [CacheDB::with_block_hash_limit].
+    /// Ordered by block number, so the oldest ones are found without a scan.
+    pub block_hashes: BTreeMap<U256, B256>,
+    /// Maximum number of retained block hashes, oldest block numbers are evicted first.
+    ///
+    /// Defaults to [BLOCK_HASH_HISTORY], use `usize::MAX` to disable eviction.
+    pub block_hash_limit: usize,
+    /// Block numbers inserted since the last [CacheDB::end_transaction], they are not evicted.
+    pending_block_hashes: HashSet<U256>,
     /// The underlying database ([DatabaseRef]) that is used to load data.
     ///
     /// Note: this is read-only, data is never written to this database.
     pub db: ExtDB,
+    /// Optional cache hit/miss counters updated by the [Database] implementation.
+    pub metrics: Option<Arc<AtomicMetrics>>,
 }
 
 impl<ExtDB: DatabaseRef> CacheDB<ExtDB> {
@@ -48,8 +62,65 @@ impl<ExtDB: DatabaseRef> CacheDB<ExtDB> {
             accounts: HashMap::new(),
             contracts,
             logs: Vec::default(),
-            block_hashes: HashMap::new(),
+            block_hashes: BTreeMap::new(),
+            block_hash_limit: BLOCK_HASH_HISTORY,
+            pending_block_hashes: HashSet::new(),
             db,
+            metrics: None,
+        }
+    }
+
+    /// Installs metrics sink that will be updated on every [Database] access.
+    pub fn with_metrics(mut self, metrics: Arc<AtomicMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Sets maximum number of retained block hashes.
+    pub fn with_block_hash_limit(mut self, limit: usize) -> Self {
+        self.block_hash_limit = limit;
+        self.evict_block_hashes();
+        self
+    }
+
+    /// Inserts a known block hash, so it doesn't need to be loaded from the underlying database.
+    pub fn set_block_hash(&mut self, number: U256, hash: B256) {
+        self.block_hashes.insert(number, hash);
+        self.pending_block_hashes.insert(number);
+        self.evict_block_hashes();
+    }
+
+    /// Ends the transaction that is executed on this database. Block hashes inserted during it
+    /// can be evicted again, and the ones above `block_hash_limit` are evicted.
+    ///
+    /// It is called by [DatabaseCommit::commit], call it after transactions that are not
+    /// committed, for example simulations.
+    pub fn end_transaction(&mut self) {
+        self.pending_block_hashes.clear();
+        self.evict_block_hashes();
+    }
+
+    /// Evicts the oldest block hashes above `block_hash_limit`.
+    ///
+    /// Hashes inserted during the current transaction are kept, so the limit can be exceeded
+    /// until [CacheDB::end_transaction].
+    fn evict_block_hashes(&mut self) {
+        let excess = self
+            .block_hashes
+            .len()
+            .saturating_sub(self.block_hash_limit);
+        if excess == 0 {
+            return;
+        }
+        let oldest: Vec<U256> = self
+            .block_hashes
+            .keys()
+            .filter(|number| !self.pending_block_hashes.contains(*number))
+            .take(excess)
+            .copied()
+            .collect();
+        for number in oldest {
+            self.block_hashes.remove(&number);
         }
     }
 
@@ -72,27 +143,67 @@ impl<ExtDB: DatabaseRef> CacheDB<ExtDB> {
         }
     }
 
+    /// Inserts raw bytecodes into the `contracts` map and returns their code hashes, in the
+    /// same order as the input.
+    ///
+    /// Codes are hashed in one pass, which is faster than inserting accounts with code one by
+    /// one when importing large number of contracts, for example from a genesis file.
+    pub fn insert_contracts(&mut self, codes: Vec<Bytes>) -> Vec<B256> {
+        self.contracts.reserve(codes.len());
+        codes
+            .into_iter()
+            .map(|code| {
+                let bytecode = Bytecode::new_raw(code);
+                let code_hash = bytecode.hash();
+                self.contracts.entry(code_hash).or_insert(bytecode);
+                code_hash
+            })
+            .collect()
+    }
+
     /// Insert account info but not override storage
     pub fn insert_account_info(&mut self, address: B160, mut info: AccountInfo) {
         self.insert_contract(&mut info);
         self.accounts.entry(address).or_default().info = info;
     }
 
+    /// Marks the account as not existing, so it is not loaded from the underlying database.
+    ///
+    /// Cached info and storage of the account are discarded.
+    pub fn insert_not_existing(&mut self, address: B160) {
+        self.accounts.insert(address, DbAccount::new_not_existing());
+    }
+
+    /// Marks all given accounts as not existing, see [CacheDB::insert_not_existing].
+    pub fn insert_not_existing_many(&mut self, addresses: impl IntoIterator<Item = B160>) {
+        let addresses = addresses.into_iter();
+        self.accounts.reserve(addresses.size_hint().0);
+        for address in addresses {
+            self.insert_not_existing(address);
+        }
+    }
+
     /// Returns the account for the given address.
     ///
     /// If the account was not found in the cache, it will be loaded from the underlying database.
     pub fn load_account(&mut self, address: B160) -> Result<&mut DbAccount, ExtDB::Error> {
         let db = &self.db;
         match self.accounts.entry(address) {
-            Entry::Occupied(entry) => Ok(entry.into_mut()),
-            Entry::Vacant(entry) => Ok(entry.insert(
-                db.basic(address)?
-                    .map(|info| DbAccount {
-                        info,
-                        ..Default::default()
-                    })
-                    .unwrap_or_else(DbAccount::new_not_existing),
-            )),
+            Entry::Occupied(entry) => {
+                record_metric(&self.metrics, |m| &m.account_hits);
+                Ok(entry.into_mut())
+            }
+            Entry::Vacant(entry) => {
+                record_metric(&self.metrics, |m| &m.account_misses);
+                Ok(entry.insert(
+                    db.basic(address)?
+                        .map(|info| DbAccount {
+                            info,
+                            ..Default::default()
+                        })
+                        .unwrap_or_else(DbAccount::new_not_existing),
+                ))
+            }
         }
     }
 
@@ -119,10 +230,154 @@ impl<ExtDB: DatabaseRef> CacheDB<ExtDB> {
         account.storage = storage.into_iter().collect();
         Ok(())
     }
+
+    /// Returns true if the account at `address` is cached, including accounts that are cached
+    /// as not existing. Underlying database is not consulted.
+    pub fn contains_account(&self, address: B160) -> bool {
+        self.accounts.contains_key(&address)
+    }
+
+    /// Returns true if the bytecode with `code_hash` is in the `contracts` map. Underlying
+    /// database is not consulted.
+    pub fn contains_contract(&self, code_hash: B256) -> bool {
+        self.contracts.contains_key(&code_hash)
+    }
+
+    /// Counts how many cached storage slots hold each value.
+    ///
+    /// Slots of accounts that don't exist, including destroyed ones, are skipped. Underlying
+    /// database is not consulted.
+    pub fn slot_value_histogram(&self) -> HashMap<U256, usize> {
+        let mut histogram = HashMap::new();
+        for account in self.accounts.values() {
+            if matches!(account.account_state, AccountState::NotExisting) {
+                continue;
+            }
+            for value in account.storage.values() {
+                *histogram.entry(*value).or_default() += 1;
+            }
+        }
+        histogram
+    }
+
+    /// Returns the bytecode of the cached account at `address`.
+    ///
+    /// Returns `None` if the account is not cached, does not exist, has empty code or its code
+    /// is not present in the `contracts` map. Underlying database is not consulted.
+    pub fn code_by_address(&self, address: B160) -> Option<&Bytecode> {
+        let account = self.accounts.get(&address)?;
+        if matches!(account.account_state, AccountState::NotExisting)
+            || account.info.code_hash == KECCAK_EMPTY
+        {
+            return None;
+        }
+        self.contracts.get(&account.info.code_hash)
+    }
+
+    /// Shrinks the capacity of the `accounts` and `contracts` maps and of the storage of every
+    /// cached account, see [DbAccount::shrink_to_fit].
+    ///
+    /// Every map is rehashed, so this is meant to be called rarely, for example after a burst
+    /// of blocks, to release memory held by transient entries.
+    pub fn shrink_to_fit(&mut self) {
+        self.accounts
+            .values_mut()
+            .for_each(DbAccount::shrink_to_fit);
+        self.accounts.shrink_to_fit();
+        self.contracts.shrink_to_fit();
+        self.pending_block_hashes.shrink_to_fit();
+    }
+
+    /// Returns the code size of the cached account at `address` without cloning its bytecode.
+    ///
+    /// Accounts with empty code return `Some(0)`. Returns `None` if the account is not cached,
+    /// does not exist or its code is not present in the `contracts` map. Underlying database is
+    /// not consulted.
+    pub fn code_size_by_address(&self, address: B160) -> Option<usize> {
+        let account = self.accounts.get(&address)?;
+        if matches!(account.account_state, AccountState::NotExisting) {
+            return None;
+        }
+        if account.info.code_hash == KECCAK_EMPTY {
+            return Some(0);
+        }
+        self.contracts
+            .get(&account.info.code_hash)
+            .map(Bytecode::len)
+    }
+
+    /// Returns the `contracts` map entries sorted by code hash.
+    ///
+    /// Iteration order of the map is not deterministic, this gives a stable order for example
+    /// for serialization, without changing how contracts are stored.
+    pub fn sorted_contracts(&self) -> impl Iterator<Item = (&B256, &Bytecode)> {
+        let mut contracts: Vec<_> = self.contracts.iter().collect();
+        contracts.sort_unstable_by_key(|(code_hash, _)| *code_hash);
+        contracts.into_iter()
+    }
+
+    /// Returns the cached value of a storage slot, or [SlotResult::NeedsFetch] if the value has
+    /// to be loaded from the underlying database.
+    ///
+    /// Slots that are not cached are known to be zero if the account does not exist or its
+    /// storage was cleared, as the [Database] implementation assumes.
+    pub fn storage_or_fetch(&self, address: B160, index: U256) -> SlotResult {
+        let account = match self.accounts.get(&address) {
+            Some(account) => account,
+            None => return SlotResult::NeedsFetch,
+        };
+        if let Some(value) = account.storage.get(&index) {
+            return SlotResult::Known(*value);
+        }
+        if account.is_storage_complete() {
+            SlotResult::Known(U256::ZERO)
+        } else {
+            SlotResult::NeedsFetch
+        }
+    }
+
+    /// Returns counts of cached accounts and contracts, in a single pass over the accounts.
+    pub fn stats(&self) -> CacheStats {
+        let mut stats = CacheStats {
+            accounts: self.accounts.len(),
+            contracts: self.contracts.len(),
+            ..Default::default()
+        };
+        for account in self.accounts.values() {
+            match account.account_state {
+                AccountState::NotExisting => continue,
+                AccountState::Touched => stats.dirty_accounts += 1,
+                AccountState::StorageCleared => {
+                    stats.dirty_accounts += 1;
+                    stats.storage_cleared_accounts += 1;
+                }
+                AccountState::None => {}
+            }
+            stats.existing_accounts += 1;
+        }
+        stats
+    }
+
+    /// Returns the address and code hash of every cached account that references bytecode
+    /// which is not present in the `contracts` map.
+    ///
+    /// Accounts with [KECCAK_EMPTY] code hash are skipped. This is a read-only consistency check
+    /// and does not consult the underlying database.
+    pub fn missing_contracts(&self) -> Vec<(B160, B256)> {
+        self.accounts
+            .iter()
+            .filter(|(_, account)| {
+                let code_hash = account.info.code_hash;
+                code_hash != KECCAK_EMPTY && !self.contracts.contains_key(&code_hash)
+            })
+            .map(|(address, account)| (*address, account.info.code_hash))
+            .collect()
+    }
 }
 
 impl<ExtDB: DatabaseRef> DatabaseCommit for CacheDB<ExtDB> {
     fn commit(&mut self, changes: HashMap<B160, Account>) {
+        self.end_transaction();
         for (address, mut account) in changes {
             if !account.is_touched() {
                 continue;
@@ -164,30 +419,46 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
 
     fn basic(&mut self, address: B160) -> Result<Option<AccountInfo>, Self::Error> {
         let basic = match self.accounts.entry(address) {
-            Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => entry.insert(
-                self.db
-                    .basic(address)?
-                    .map(|info| DbAccount {
-                        info,
-                        ..Default::default()
-                    })
-                    .unwrap_or_else(DbAccount::new_not_existing),
-            ),
+            Entry::Occupied(entry) => {
+                record_metric(&self.metrics, |m| &m.account_hits);
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => {
+                record_metric(&self.metrics, |m| &m.account_misses);
+                entry.insert(
+                    self.db
+                        .basic(address)?
+                        .map(|info| DbAccount {
+                            info,
+                            ..Default::default()
+                        })
+                        .unwrap_or_else(DbAccount::new_not_existing),
+                )
+            }
         };
         Ok(basic.info())
     }
 
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         match self.contracts.entry(code_hash) {
-            Entry::Occupied(entry) => Ok(entry.get().clone()),
+            Entry::Occupied(entry) => {
+                record_metric(&self.metrics, |m| &m.bytecode_hits);
+                Ok(entry.get().clone())
+            }
             Entry::Vacant(entry) => {
+                record_metric(&self.metrics, |m| &m.bytecode_misses);
                 // if you return code bytes when basic fn is called this function is not needed.
                 Ok(entry.insert(self.db.code_by_hash(code_hash)?).clone())
             }
         }
     }
 
+    /// Reads the prefix from the cached code, or from the underlying database without caching
+    /// the code.
+    fn code_prefix(&mut self, code_hash: B256, len: usize) -> Result<Bytes, Self::Error> {
+        DatabaseRef::code_prefix(self, code_hash, len)
+    }
+
     /// Get the value in an account's storage slot.
     ///
     /// It is assumed that account is already loaded.
@@ -196,14 +467,16 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
             Entry::Occupied(mut acc_entry) => {
                 let acc_entry = acc_entry.get_mut();
                 match acc_entry.storage.entry(index) {
-                    Entry::Occupied(entry) => Ok(*entry.get()),
+                    Entry::Occupied(entry) => {
+                        record_metric(&self.metrics, |m| &m.storage_hits);
+                        Ok(*entry.get())
+                    }
                     Entry::Vacant(entry) => {
-                        if matches!(
-                            acc_entry.account_state,
-                            AccountState::StorageCleared | AccountState::NotExisting
-                        ) {
+                        if acc_entry.is_storage_complete() {
+                            record_metric(&self.metrics, |m| &m.storage_hits);
                             Ok(U256::ZERO)
                         } else {
+                            record_metric(&self.metrics, |m| &m.storage_misses);
                             let slot = self.db.storage(address, index)?;
                             entry.insert(slot);
                             Ok(slot)
@@ -213,8 +486,10 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
             }
             Entry::Vacant(acc_entry) => {
                 // acc needs to be loaded for us to access slots.
+                record_metric(&self.metrics, |m| &m.account_misses);
                 let info = self.db.basic(address)?;
                 let (account, value) = if info.is_some() {
+                    record_metric(&self.metrics, |m| &m.storage_misses);
                     let value = self.db.storage(address, index)?;
                     let mut account: DbAccount = info.into();
                     account.storage.insert(index, value);
@@ -229,14 +504,14 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
     }
 
     fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
-        match self.block_hashes.entry(number) {
-            Entry::Occupied(entry) => Ok(*entry.get()),
-            Entry::Vacant(entry) => {
-                let hash = self.db.block_hash(number)?;
-                entry.insert(hash);
-                Ok(hash)
-            }
+        if let Some(hash) = self.block_hashes.get(&number) {
+            record_metric(&self.metrics, |m| &m.block_hash_hits);
+            return Ok(*hash);
         }
+        record_metric(&self.metrics, |m| &m.block_hash_misses);
+        let hash = self.db.block_hash(number)?;
+        self.set_block_hash(number, hash);
+        Ok(hash)
     }
 }
 
@@ -257,6 +532,16 @@ impl<ExtDB: DatabaseRef> DatabaseRef for CacheDB<ExtDB> {
         }
     }
 
+    fn code_prefix(&self, code_hash: B256, len: usize) -> Result<Bytes, Self::Error> {
+        match self.contracts.get(&code_hash) {
+            Some(entry) => {
+                let code = entry.original_bytes();
+                Ok(code.slice(..len.min(code.len())))
+            }
+            None => self.db.code_prefix(code_hash, len),
+        }
+    }
+
     fn storage(&self, address: B160, index: U256) -> Result<U256, Self::Error> {
         match self.accounts.get(&address) {
             Some(acc_entry) => match acc_entry.storage.get(&index) {
@@ -284,6 +569,17 @@ impl<ExtDB: DatabaseRef> DatabaseRef for CacheDB<ExtDB> {
     }
 }
 
+/// Bumps the selected counter if metrics are installed.
+#[inline]
+fn record_metric<F: FnOnce(&AtomicMetrics) -> &AtomicU64>(
+    metrics: &Option<Arc<AtomicMetrics>>,
+    counter: F,
+) {
+    if let Some(metrics) = metrics.as_deref() {
+        AtomicMetrics::record(counter(metrics));
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct DbAccount {
     pub info: AccountInfo,
@@ -307,6 +603,22 @@ impl DbAccount {
             Some(self.info.clone())
         }
     }
+
+    /// Returns true if all non zero slots of the account are in `storage`, either because the
+    /// account does not exist or because its storage was cleared.
+    pub fn is_storage_complete(&self) -> bool {
+        matches!(
+            self.account_state,
+            AccountState::StorageCleared | AccountState::NotExisting
+        )
+    }
+
+    /// Shrinks the capacity of the storage map as much as possible.
+    ///
+    /// Shrinking rehashes the whole map, so it should be called sparingly.
+    pub fn shrink_to_fit(&mut self) {
+        self.storage.shrink_to_fit();
+    }
 }
 
 impl From<Option<AccountInfo>> for DbAccount {
@@ -333,6 +645,33 @@ impl From<AccountInfo> for DbAccount {
     }
 }
 
+/// Cached value of a storage slot, see [CacheDB::storage_or_fetch].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotResult {
+    /// Value of the slot is known without a database access.
+    Known(U256),
+    /// Slot is not cached and needs to be loaded from the underlying database.
+    NeedsFetch,
+}
+
+/// Counts of cached entries of a [CacheDB], see [CacheDB::stats].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of cached accounts, including not existing ones.
+    pub accounts: usize,
+    /// Number of accounts that are not [AccountState::NotExisting].
+    ///
+    /// Selfdestructed accounts are committed as not existing, so they are not counted.
+    pub existing_accounts: usize,
+    /// Number of accounts changed by [DatabaseCommit::commit], [AccountState::Touched] or
+    /// [AccountState::StorageCleared].
+    pub dirty_accounts: usize,
+    /// Number of accounts with [AccountState::StorageCleared], created or recreated ones.
+    pub storage_cleared_accounts: usize,
+    /// Number of distinct bytecodes in the `contracts` map.
+    pub contracts: usize,
+}
+
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub enum AccountState {
     /// Before Spurious Dragon hardfork there was a difference between empty and not existing.
@@ -434,8 +773,14 @@ impl Database for BenchmarkDB {
 
 #[cfg(test)]
 mod tests {
-    use super::{CacheDB, EmptyDB};
-    use crate::primitives::{db::Database, AccountInfo, U256};
+    use super::{AccountState, CacheDB, CacheStats, EmptyDB, SlotResult};
+    use crate::db::{AtomicMetrics, DatabaseCommit, MetricsSnapshot};
+    use crate::interpreter::analysis::to_analysed;
+    use crate::primitives::{
+        db::Database, hex_literal::hex, keccak256, AccountInfo, Bytecode, Bytes, ExecutionResult,
+        HashMap, Output, TransactTo, B160, B256, KECCAK_EMPTY, U256,
+    };
+    use alloc::sync::Arc;
 
     #[test]
     pub fn test_insert_account_storage() {
@@ -458,6 +803,32 @@ mod tests {
         assert_eq!(new_state.storage(account, key), Ok(value));
     }
 
+    #[test]
+    pub fn test_storage_b256() {
+        use crate::db::DatabaseRef;
+
+        let account: B160 = 42.into();
+        let mut state = CacheDB::new(EmptyDB::default());
+        state.insert_account_info(account, AccountInfo::default());
+        let _ = state.insert_account_storage(account, U256::from(0x0100), U256::MAX);
+
+        let mut key = [0u8; 32];
+        key[30] = 0x01;
+        let key = B256(key);
+        assert_eq!(
+            DatabaseRef::storage_b256(&state, account, key),
+            Ok(B256([0xff; 32]))
+        );
+        assert_eq!(
+            Database::storage_b256(&mut state, account, key),
+            Ok(B256([0xff; 32]))
+        );
+        assert_eq!(
+            Database::storage_b256(&mut state, account, B256::zero()),
+            Ok(B256::zero())
+        );
+    }
+
     #[test]
     pub fn test_replace_account_storage() {
         let account = 42.into();
@@ -482,4 +853,440 @@ mod tests {
         assert_eq!(new_state.storage(account, key0), Ok(U256::ZERO));
         assert_eq!(new_state.storage(account, key1), Ok(value1));
     }
+
+    #[test]
+    pub fn test_insert_not_existing_many() {
+        let existing: B160 = 1.into();
+        let mut init_state = CacheDB::new(EmptyDB::default());
+        init_state.insert_account_info(existing, AccountInfo::from_balance(U256::from(1)));
+        let _ = init_state.insert_account_storage(existing, U256::from(1), U256::from(2));
+
+        let mut state = CacheDB::new(init_state);
+        state.insert_not_existing_many((1..=3u64).map(B160::from));
+
+        assert_eq!(state.accounts.len(), 3);
+        assert_eq!(state.basic(existing), Ok(None));
+        assert_eq!(state.storage(existing, U256::from(1)), Ok(U256::ZERO));
+        assert_eq!(state.basic(3.into()), Ok(None));
+    }
+
+    #[test]
+    pub fn test_shrink_to_fit() {
+        let account: B160 = 1.into();
+        let mut state = CacheDB::new(EmptyDB::default());
+        state.insert_account_info(account, AccountInfo::default());
+        for slot in 0..1000u64 {
+            let _ = state.insert_account_storage(account, U256::from(slot), U256::from(1));
+        }
+        state
+            .accounts
+            .get_mut(&account)
+            .unwrap()
+            .storage
+            .retain(|slot, _| *slot == U256::ZERO);
+
+        state.shrink_to_fit();
+
+        let storage = &state.accounts[&account].storage;
+        assert!(storage.capacity() < 1000);
+        assert_eq!(storage.get(&U256::ZERO), Some(&U256::from(1)));
+    }
+
+    #[test]
+    pub fn test_insert_contracts() {
+        let mut state = CacheDB::new(EmptyDB::default());
+        let codes = vec![
+            Bytes::from_static(&[0x60, 0x00]),
+            Bytes::new(),
+            Bytes::from_static(&[0x60, 0x00]),
+        ];
+        let hashes = state.insert_contracts(codes.clone());
+
+        assert_eq!(
+            hashes,
+            vec![keccak256(&codes[0]), KECCAK_EMPTY, keccak256(&codes[0])]
+        );
+        assert_eq!(state.contracts.len(), 3);
+        assert_eq!(
+            state.code_by_hash(hashes[0]).unwrap().bytes(),
+            &Bytes::from_static(&[0x60, 0x00])
+        );
+    }
+
+    #[test]
+    pub fn test_contains() {
+        let (account, not_existing) = (B160::from(1), B160::from(2));
+        let mut state = CacheDB::new(EmptyDB::default());
+        let mut info = AccountInfo {
+            code: Some(Bytecode::new_raw(Bytes::from_static(&[0x00]))),
+            ..Default::default()
+        };
+        state.insert_contract(&mut info);
+        let code_hash = info.code_hash;
+        state.insert_account_info(account, info);
+        state.insert_not_existing(not_existing);
+
+        assert!(state.contains_account(account));
+        assert!(state.contains_account(not_existing));
+        assert!(!state.contains_account(B160::from(3)));
+        assert!(state.contains_contract(code_hash));
+        assert!(state.contains_contract(KECCAK_EMPTY));
+        assert!(!state.contains_contract(B256::repeat_byte(1)));
+    }
+
+    #[test]
+    pub fn test_code_prefix() {
+        let mut state = CacheDB::new(EmptyDB::default());
+        let mut info = AccountInfo {
+            code: Some(to_analysed(Bytecode::new_raw(Bytes::from_static(&[
+                0xef, 0x01, 0x00, 0xaa,
+            ])))),
+            ..Default::default()
+        };
+        state.insert_contract(&mut info);
+
+        assert_eq!(
+            Database::code_prefix(&mut state, info.code_hash, 3).unwrap(),
+            Bytes::from_static(&[0xef, 0x01, 0x00])
+        );
+        // analysed code is padded, padding is not part of the prefix.
+        assert_eq!(
+            crate::db::DatabaseRef::code_prefix(&state, info.code_hash, 64).unwrap(),
+            Bytes::from_static(&[0xef, 0x01, 0x00, 0xaa])
+        );
+        // code that is not cached is read from the underlying database.
+        assert!(Database::code_prefix(&mut state, B256::repeat_byte(1), 3)
+            .unwrap()
+            .is_empty());
+        assert!(!state.contracts.contains_key(&B256::repeat_byte(1)));
+    }
+
+    #[test]
+    pub fn test_slot_value_histogram() {
+        let (first, second, destroyed) = (B160::from(1), B160::from(2), B160::from(3));
+        let mut state = CacheDB::new(EmptyDB::default());
+        state.insert_account_info(first, AccountInfo::default());
+        state.insert_account_info(second, AccountInfo::default());
+        for (slot, value) in [(0u64, 1u64), (1, 1), (2, 7)] {
+            state
+                .insert_account_storage(first, U256::from(slot), U256::from(value))
+                .unwrap();
+        }
+        state
+            .insert_account_storage(second, U256::ZERO, U256::from(1))
+            .unwrap();
+        // slots left in the cache of a destroyed account are not counted.
+        state.insert_not_existing(destroyed);
+        state
+            .insert_account_storage(destroyed, U256::ZERO, U256::from(7))
+            .unwrap();
+
+        let histogram = state.slot_value_histogram();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram[&U256::from(1)], 3);
+        assert_eq!(histogram[&U256::from(7)], 1);
+    }
+
+    #[test]
+    pub fn test_sorted_contracts() {
+        let mut state = CacheDB::new(EmptyDB::default());
+        let hashes = state.insert_contracts(
+            (0u8..16)
+                .map(|i| Bytes::copy_from_slice(&[0x60, i, 0x00]))
+                .collect(),
+        );
+
+        let sorted: Vec<_> = state.sorted_contracts().collect();
+        assert_eq!(sorted.len(), hashes.len() + 2);
+        assert!(sorted.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(sorted[0].0, &B256::zero());
+        for (code_hash, code) in sorted {
+            assert_eq!(code, &state.contracts[code_hash]);
+        }
+    }
+
+    #[test]
+    pub fn test_storage_or_fetch() {
+        let (account, cleared, missing) = (B160::from(1), B160::from(2), B160::from(3));
+        let mut state = CacheDB::new(EmptyDB::default());
+        state.insert_account_info(account, AccountInfo::default());
+        state
+            .insert_account_storage(account, U256::from(1), U256::from(5))
+            .unwrap();
+        state
+            .replace_account_storage(
+                cleared,
+                [(U256::from(1), U256::from(6))].into_iter().collect(),
+            )
+            .unwrap();
+        state.insert_not_existing(missing);
+
+        assert_eq!(
+            state.storage_or_fetch(account, U256::from(1)),
+            SlotResult::Known(U256::from(5))
+        );
+        assert_eq!(
+            state.storage_or_fetch(account, U256::from(2)),
+            SlotResult::NeedsFetch
+        );
+        assert_eq!(
+            state.storage_or_fetch(cleared, U256::from(2)),
+            SlotResult::Known(U256::ZERO)
+        );
+        assert_eq!(
+            state.storage_or_fetch(missing, U256::from(2)),
+            SlotResult::Known(U256::ZERO)
+        );
+        assert_eq!(
+            state.storage_or_fetch(B160::from(4), U256::from(1)),
+            SlotResult::NeedsFetch
+        );
+    }
+
+    #[test]
+    pub fn test_stats() {
+        let mut state = CacheDB::new(EmptyDB::default());
+        state.insert_account_info(1.into(), AccountInfo::default());
+        state.insert_not_existing(2.into());
+        state.insert_account_info(
+            3.into(),
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from_static(&[0x00]))),
+                ..Default::default()
+            },
+        );
+        state.accounts.get_mut(&1.into()).unwrap().account_state = AccountState::Touched;
+        state.accounts.get_mut(&3.into()).unwrap().account_state = AccountState::StorageCleared;
+
+        assert_eq!(
+            state.stats(),
+            CacheStats {
+                accounts: 3,
+                existing_accounts: 2,
+                dirty_accounts: 2,
+                storage_cleared_accounts: 1,
+                // empty code is inserted twice, by KECCAK_EMPTY and by zero hash.
+                contracts: 3,
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_missing_contracts() {
+        let mut state = CacheDB::new(EmptyDB::default());
+        let with_code: B160 = 1.into();
+        let without_code: B160 = 2.into();
+        let dangling: B160 = 3.into();
+        state.insert_account_info(
+            with_code,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from_static(&[0x00]))),
+                ..Default::default()
+            },
+        );
+        state.insert_account_info(without_code, AccountInfo::default());
+        let dangling_hash = B256::repeat_byte(0xaa);
+        state.insert_account_info(
+            dangling,
+            AccountInfo {
+                code_hash: dangling_hash,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(state.missing_contracts(), vec![(dangling, dangling_hash)]);
+    }
+
+    #[test]
+    pub fn test_code_by_address() {
+        let mut state = CacheDB::new(EmptyDB::default());
+        let with_code: B160 = 1.into();
+        let without_code: B160 = 2.into();
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        state.insert_account_info(
+            with_code,
+            AccountInfo {
+                code: Some(code.clone()),
+                ..Default::default()
+            },
+        );
+        state.insert_account_info(without_code, AccountInfo::default());
+
+        assert_eq!(state.code_by_address(with_code), Some(&code));
+        assert_eq!(state.code_by_address(without_code), None);
+        assert_eq!(state.code_by_address(3.into()), None);
+    }
+
+    #[test]
+    pub fn test_code_size_by_address() {
+        let mut state = CacheDB::new(EmptyDB::default());
+        let with_code: B160 = 1.into();
+        let without_code: B160 = 2.into();
+        let dangling: B160 = 3.into();
+        let not_existing: B160 = 4.into();
+        state.insert_account_info(
+            with_code,
+            AccountInfo {
+                // analysed bytecode is padded, size is the original length.
+                code: Some(to_analysed(Bytecode::new_raw(Bytes::from_static(&[
+                    0x60, 0x00, 0x00,
+                ])))),
+                ..Default::default()
+            },
+        );
+        state.insert_account_info(without_code, AccountInfo::default());
+        state.insert_account_info(
+            dangling,
+            AccountInfo {
+                code_hash: B256::repeat_byte(0xaa),
+                ..Default::default()
+            },
+        );
+        state.insert_not_existing(not_existing);
+
+        assert_eq!(state.code_size_by_address(with_code), Some(3));
+        assert_eq!(state.code_size_by_address(without_code), Some(0));
+        assert_eq!(state.code_size_by_address(dangling), None);
+        assert_eq!(state.code_size_by_address(not_existing), None);
+        assert_eq!(state.code_size_by_address(5.into()), None);
+    }
+
+    #[test]
+    pub fn test_metrics() {
+        let account: B160 = 42.into();
+        let mut init_state = CacheDB::new(EmptyDB::default());
+        init_state.insert_account_info(
+            account,
+            AccountInfo {
+                nonce: 1,
+                ..Default::default()
+            },
+        );
+        let _ = init_state.insert_account_storage(account, U256::from(1), U256::from(2));
+
+        let metrics = Arc::new(AtomicMetrics::default());
+        let mut state = CacheDB::new(init_state).with_metrics(metrics.clone());
+
+        // first access falls through, second is served from cache.
+        state.basic(account).unwrap();
+        state.basic(account).unwrap();
+        state.storage(account, U256::from(1)).unwrap();
+        state.storage(account, U256::from(1)).unwrap();
+        // account not known to the cache is loaded together with the slot.
+        state.storage(7.into(), U256::from(1)).unwrap();
+        state.code_by_hash(B256::repeat_byte(1)).unwrap();
+        state.code_by_hash(B256::repeat_byte(1)).unwrap();
+        state.block_hash(U256::from(10)).unwrap();
+        state.block_hash(U256::from(10)).unwrap();
+        state.block_hash(U256::from(11)).unwrap();
+
+        assert_eq!(
+            metrics.snapshot(),
+            MetricsSnapshot {
+                account_hits: 1,
+                account_misses: 2,
+                storage_hits: 1,
+                storage_misses: 1,
+                bytecode_hits: 1,
+                bytecode_misses: 1,
+                block_hash_hits: 1,
+                block_hash_misses: 2,
+            }
+        );
+
+        metrics.reset();
+        assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    pub fn test_block_hash_retention() {
+        let mut state = CacheDB::new(EmptyDB::default()).with_block_hash_limit(2);
+        for number in 1..=3 {
+            state.block_hash(U256::from(number)).unwrap();
+        }
+        // hashes loaded since the last commit are not evicted.
+        assert_eq!(state.block_hashes.len(), 3);
+
+        state.commit(HashMap::new());
+        let mut numbers: Vec<_> = state.block_hashes.keys().copied().collect();
+        numbers.sort();
+        assert_eq!(numbers, vec![U256::from(2), U256::from(3)]);
+
+        state.set_block_hash(U256::from(10), B256::repeat_byte(1));
+        assert_eq!(state.block_hashes.len(), 3);
+        state.commit(HashMap::new());
+        assert_eq!(state.block_hashes.len(), 2);
+        assert!(!state.block_hashes.contains_key(&U256::from(2)));
+        assert_eq!(
+            state.block_hash(U256::from(10)).unwrap(),
+            B256::repeat_byte(1)
+        );
+    }
+
+    #[test]
+    pub fn test_block_hash_eviction_without_commit() {
+        let contract: B160 = 0x10.into();
+        // NUMBER, PUSH1 1, SWAP1, SUB, BLOCKHASH: hash of the parent block.
+        let code = hex!("436001900340");
+        let mut db = CacheDB::new(EmptyDB::default()).with_block_hash_limit(2);
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(U256::ZERO, 0, Bytecode::new_raw(Bytes::from_static(&code))),
+        );
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        for number in 10..20 {
+            evm.env.block.number = U256::from(number);
+            evm.transact().unwrap();
+            let db = evm.db().unwrap();
+            assert!(db.block_hashes.len() <= 3);
+            db.end_transaction();
+            assert!(db.block_hashes.len() <= 2);
+        }
+
+        let numbers: Vec<_> = evm.db.unwrap().block_hashes.into_keys().collect();
+        assert_eq!(numbers, vec![U256::from(18), U256::from(19)]);
+    }
+
+    #[test]
+    pub fn test_block_hash_opcode_boundary() {
+        let contract: B160 = 0x10.into();
+        // BLOCKHASH of blocks 44, 43 and 299 stored at memory offsets 0, 32 and 64, then returned.
+        let code = hex!("602c40600052 602b40602052 61012b40604052 60606000f3");
+        let mut db = CacheDB::new(EmptyDB::default()).with_block_hash_limit(1);
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(U256::ZERO, 0, Bytecode::new_raw(Bytes::from_static(&code))),
+        );
+        // known hash does not need to be loaded from the underlying database.
+        db.set_block_hash(U256::from(299), B256::repeat_byte(0xaa));
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.block.number = U256::from(300);
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        let ExecutionResult::Success {
+            output: Output::Call(output),
+            ..
+        } = evm.transact_commit().unwrap()
+        else {
+            panic!("call should succeed");
+        };
+
+        // block 44 is the oldest reachable one, block 43 is out of range.
+        let hash_44 = keccak256(&U256::from(44).to_be_bytes::<{ U256::BYTES }>());
+        assert_eq!(&output[..32], hash_44.as_bytes());
+        assert_eq!(&output[32..64], B256::zero().as_bytes());
+        assert_eq!(&output[64..], B256::repeat_byte(0xaa).as_bytes());
+
+        // only the most recent block hash is retained after the commit.
+        let db = evm.db.unwrap();
+        assert_eq!(db.block_hashes.len(), 1);
+        assert_eq!(
+            db.block_hashes.get(&U256::from(299)),
+            Some(&B256::repeat_byte(0xaa))
+        );
+    }
 }
@@ -0,0 +1,69 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Cache effectiveness counters that can be shared with [CacheDB](crate::db::CacheDB).
+///
+/// A hit means the value was served from the cache, a miss means the request fell through to
+/// the underlying database. Counters are updated with relaxed ordering and are meant for
+/// observability only.
+#[derive(Debug, Default)]
+pub struct AtomicMetrics {
+    pub account_hits: AtomicU64,
+    pub account_misses: AtomicU64,
+    pub storage_hits: AtomicU64,
+    pub storage_misses: AtomicU64,
+    pub bytecode_hits: AtomicU64,
+    pub bytecode_misses: AtomicU64,
+    pub block_hash_hits: AtomicU64,
+    pub block_hash_misses: AtomicU64,
+}
+
+/// Point in time copy of [AtomicMetrics].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetricsSnapshot {
+    pub account_hits: u64,
+    pub account_misses: u64,
+    pub storage_hits: u64,
+    pub storage_misses: u64,
+    pub bytecode_hits: u64,
+    pub bytecode_misses: u64,
+    pub block_hash_hits: u64,
+    pub block_hash_misses: u64,
+}
+
+impl AtomicMetrics {
+    /// Returns current values of all counters.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            account_hits: self.account_hits.load(Ordering::Relaxed),
+            account_misses: self.account_misses.load(Ordering::Relaxed),
+            storage_hits: self.storage_hits.load(Ordering::Relaxed),
+            storage_misses: self.storage_misses.load(Ordering::Relaxed),
+            bytecode_hits: self.bytecode_hits.load(Ordering::Relaxed),
+            bytecode_misses: self.bytecode_misses.load(Ordering::Relaxed),
+            block_hash_hits: self.block_hash_hits.load(Ordering::Relaxed),
+            block_hash_misses: self.block_hash_misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets all counters to zero.
+    pub fn reset(&self) {
+        for counter in [
+            &self.account_hits,
+            &self.account_misses,
+            &self.storage_hits,
+            &self.storage_misses,
+            &self.bytecode_hits,
+            &self.bytecode_misses,
+            &self.block_hash_hits,
+            &self.block_hash_misses,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
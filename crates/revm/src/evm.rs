@@ -1,7 +1,9 @@
-use crate::primitives::{specification, EVMError, EVMResult, Env, ExecutionResult, SpecId};
+use crate::primitives::{
+    specification, Bytes, EVMError, EVMResult, Env, ExecutionResult, SpecId, TxEnv, B160,
+};
 use crate::{
     db::{Database, DatabaseCommit, DatabaseRef, RefDBWrapper},
-    evm_impl::{EVMImpl, Transact},
+    evm_impl::{EVMImpl, Transact, VerifiedTx},
     inspectors::NoOpInspector,
     Inspector,
 };
@@ -44,7 +46,7 @@ impl<DB> Default for EVM<DB> {
 impl<DB: Database + DatabaseCommit> EVM<DB> {
     /// Execute transaction and apply result to database
     pub fn transact_commit(&mut self) -> Result<ExecutionResult, EVMError<DB::Error>> {
-        let ResultAndState { result, state } = self.transact()?;
+        let ResultAndState { result, state, .. } = self.transact()?;
         self.db.as_mut().unwrap().commit(state);
         Ok(result)
     }
@@ -53,7 +55,7 @@ impl<DB: Database + DatabaseCommit> EVM<DB> {
         &mut self,
         inspector: INSP,
     ) -> Result<ExecutionResult, EVMError<DB::Error>> {
-        let ResultAndState { result, state } = self.inspect(inspector)?;
+        let ResultAndState { result, state, .. } = self.inspect(inspector)?;
         self.db.as_mut().unwrap().commit(state);
         Ok(result)
     }
@@ -79,6 +81,54 @@ impl<DB: Database> EVM<DB> {
             panic!("Database needs to be set");
         }
     }
+
+    /// Run all checks of the transaction without executing it.
+    ///
+    /// Returned [VerifiedTx] can be executed with [EVM::transact_preverified] as long as the
+    /// environment and the caller nonce don't change.
+    pub fn preverify_transaction(&mut self) -> Result<VerifiedTx, EVMError<DB::Error>> {
+        if let Some(db) = self.db.as_mut() {
+            let mut noop = NoOpInspector {};
+            let out = evm_inner::<DB, false>(&mut self.env, db, &mut noop).preverify_transaction();
+            out
+        } else {
+            panic!("Database needs to be set");
+        }
+    }
+
+    /// Execute transaction verified by [EVM::preverify_transaction] without writing to DB,
+    /// return change state.
+    pub fn transact_preverified(&mut self, verified: VerifiedTx) -> EVMResult<DB::Error> {
+        if let Some(db) = self.db.as_mut() {
+            let mut noop = NoOpInspector {};
+            let out =
+                evm_inner::<DB, false>(&mut self.env, db, &mut noop).transact_preverified(verified);
+            out
+        } else {
+            panic!("Database needs to be set");
+        }
+    }
+
+    /// Execute system call to `contract` without writing to DB, return change state.
+    ///
+    /// System calls are executed outside of transactions, for example EIP-4788 beacon root
+    /// update at the start of the block. Caller is [SYSTEM_ADDRESS](crate::primitives::SYSTEM_ADDRESS)
+    /// with [SYSTEM_CALL_GAS_LIMIT](crate::primitives::SYSTEM_CALL_GAS_LIMIT) gas. Transaction
+    /// checks are skipped, no fee is paid and gas is not counted against the block gas limit.
+    ///
+    /// Transaction environment is restored after the call.
+    pub fn transact_system_call(&mut self, contract: B160, data: Bytes) -> EVMResult<DB::Error> {
+        if let Some(db) = self.db.as_mut() {
+            let tx = core::mem::replace(&mut self.env.tx, TxEnv::new_system_call(contract, data));
+            let mut noop = NoOpInspector {};
+            let out =
+                evm_inner::<DB, false>(&mut self.env, db, &mut noop).transact_system_call(contract);
+            self.env.tx = tx;
+            out
+        } else {
+            panic!("Database needs to be set");
+        }
+    }
 }
 
 impl<'a, DB: DatabaseRef> EVM<DB> {
@@ -148,7 +198,7 @@ macro_rules! create_evm {
             $db,
             $env,
             $inspector,
-            Precompiles::new(to_precompile_id($spec::SPEC_ID)).clone(),
+            Precompiles::new(to_precompile_id($spec::SPEC_ID)),
         )) as Box<dyn Transact<DB::Error> + 'a>
     };
 }
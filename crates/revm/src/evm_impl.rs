@@ -5,18 +5,17 @@ use crate::interpreter::{
 };
 use crate::journaled_state::{is_precompile, JournalCheckpoint};
 use crate::primitives::{
-    create2_address, create_address, keccak256, Account, AnalysisKind, Bytecode, Bytes, EVMError,
-    EVMResult, Env, ExecutionResult, HashMap, InvalidTransaction, Log, Output, ResultAndState,
-    Spec,
+    create2_address, create_address, keccak256, Account, AnalysisKind, Bytecode, Bytes, CfgEnv,
+    ColdAccess, CreatedContract, EVMError, EVMResult, Env, ExecutionResult, HashMap,
+    InvalidTransaction, Log, Output, ResultAndState, Spec,
     SpecId::{self, *},
-    TransactTo, B160, B256, U256,
+    TransactTo, B160, B256, KECCAK_EMPTY, U256,
 };
 use crate::{db::Database, journaled_state::JournaledState, precompile, Inspector};
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::{cmp::min, marker::PhantomData};
-use revm_interpreter::gas::initial_tx_gas;
-use revm_interpreter::MAX_CODE_SIZE;
+use revm_interpreter::gas::{initial_tx_gas, initial_tx_gas_with};
 use revm_precompile::{Precompile, Precompiles};
 
 pub struct EVMData<'a, DB: Database> {
@@ -24,11 +23,15 @@ pub struct EVMData<'a, DB: Database> {
     pub journaled_state: JournaledState,
     pub db: &'a mut DB,
     pub error: Option<DB::Error>,
+    /// Number of instructions that can still be executed in this transaction.
+    /// `None` if there is no limit.
+    #[cfg(feature = "optional_instruction_limit")]
+    pub instructions_remaining: Option<u64>,
 }
 
 pub struct EVMImpl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> {
     data: EVMData<'a, DB>,
-    precompiles: Precompiles,
+    precompiles: &'static Precompiles,
     inspector: &'a mut dyn Inspector<DB>,
     _phantomdata: PhantomData<GSPEC>,
 }
@@ -36,6 +39,7 @@ pub struct EVMImpl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> {
 struct PreparedCreate {
     gas: Gas,
     created_address: B160,
+    init_code_hash: B256,
     checkpoint: JournalCheckpoint,
     contract: Box<Contract>,
 }
@@ -59,11 +63,87 @@ struct CallResult {
     return_value: Bytes,
 }
 
+/// Transaction that passed [Transact::preverify_transaction].
+///
+/// It records the spec, the configuration and the parts of the transaction and block
+/// environment the checks depend on, so it can't be used to execute a different transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedTx {
+    spec_id: SpecId,
+    initial_gas: u64,
+    caller_nonce: u64,
+    caller: B160,
+    gas_limit: u64,
+    gas_price: U256,
+    gas_priority_fee: Option<U256>,
+    transact_to: TransactTo,
+    value: U256,
+    data: Bytes,
+    chain_id: Option<u64>,
+    nonce: Option<u64>,
+    access_list: Vec<(B160, Vec<U256>)>,
+    basefee: U256,
+    block_gas_limit: U256,
+    cfg: CfgEnv,
+}
+
+impl VerifiedTx {
+    /// Spec the transaction was verified under.
+    pub fn spec_id(&self) -> SpecId {
+        self.spec_id
+    }
+
+    /// Intrinsic gas of the transaction.
+    pub fn initial_gas(&self) -> u64 {
+        self.initial_gas
+    }
+
+    /// Nonce of the caller account at the time of verification.
+    pub fn caller_nonce(&self) -> u64 {
+        self.caller_nonce
+    }
+
+    fn matches_env(&self, env: &Env) -> bool {
+        self.caller == env.tx.caller
+            && self.gas_limit == env.tx.gas_limit
+            && self.gas_price == env.tx.gas_price
+            && self.gas_priority_fee == env.tx.gas_priority_fee
+            && self.transact_to == env.tx.transact_to
+            && self.value == env.tx.value
+            && self.chain_id == env.tx.chain_id
+            && self.nonce == env.tx.nonce
+            && self.access_list == env.tx.access_list
+            && self.basefee == env.block.basefee
+            && self.block_gas_limit == env.block.gas_limit
+            && self.data == env.tx.data
+            && self.cfg == env.cfg
+    }
+}
+
 pub trait Transact<DBError> {
     /// Do transaction.
     /// InstructionResult InstructionResult, Output for call or Address if we are creating
     /// contract, gas spend, gas refunded, State that needs to be applied.
     fn transact(&mut self) -> EVMResult<DBError>;
+
+    /// Runs all transaction checks (block env, intrinsic gas, nonce and balance) without
+    /// executing the transaction.
+    fn preverify_transaction(&mut self) -> Result<VerifiedTx, EVMError<DBError>>;
+
+    /// Executes the transaction that was checked by [Transact::preverify_transaction],
+    /// skipping the checks. Only the caller balance is checked again.
+    ///
+    /// Returns [EVMError::VerifiedTxMismatch] if the spec, configuration, transaction or block
+    /// environment or caller nonce changed since the verification.
+    fn transact_preverified(&mut self, verified: VerifiedTx) -> EVMResult<DBError>;
+
+    /// Executes a system call to `contract` with the transaction environment built by
+    /// [crate::primitives::TxEnv::new_system_call].
+    ///
+    /// Transaction is not validated, intrinsic gas is not charged, caller nonce and balance are
+    /// not changed and no fee is paid to the block beneficiary. After EIP-161 the caller is
+    /// removed from the state if it is empty.
+    fn transact_system_call(&mut self, contract: B160) -> EVMResult<DBError>;
 }
 
 impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB, INSPECT> {
@@ -80,40 +160,128 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         }
         Ok(())
     }
-}
 
-impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact<DB::Error>
-    for EVMImpl<'a, GSPEC, DB, INSPECT>
-{
-    fn transact(&mut self) -> EVMResult<DB::Error> {
+    /// Loads coinbase and access list, accounts that start warm.
+    fn load_warm_accounts(&mut self) -> Result<(), EVMError<DB::Error>> {
+        // load coinbase
+        // EIP-3651: Warm COINBASE. Starts the `COINBASE` address warm
+        if GSPEC::enabled(SHANGHAI) {
+            self.data
+                .journaled_state
+                .initial_account_load(self.data.env.block.coinbase, &[], self.data.db)
+                .map_err(EVMError::Database)?;
+        }
+        self.load_access_list()?;
+        #[cfg(feature = "optional_warm_injection")]
+        self.load_injected_warm_accounts()?;
+        Ok(())
+    }
+
+    /// Loads [crate::primitives::TxEnv::warm_addresses] and
+    /// [crate::primitives::TxEnv::warm_storage].
+    #[cfg(feature = "optional_warm_injection")]
+    fn load_injected_warm_accounts(&mut self) -> Result<(), EVMError<DB::Error>> {
+        for address in self.data.env.tx.warm_addresses.iter() {
+            self.data
+                .journaled_state
+                .initial_account_load(*address, &[], self.data.db)
+                .map_err(EVMError::Database)?;
+        }
+        for (address, slot) in self.data.env.tx.warm_storage.iter() {
+            self.data
+                .journaled_state
+                .initial_account_load(*address, &[*slot], self.data.db)
+                .map_err(EVMError::Database)?;
+        }
+        Ok(())
+    }
+
+    /// Runs transaction checks and loads the accounts that start warm.
+    ///
+    /// Returns intrinsic gas and the caller nonce.
+    fn validate_transaction(&mut self) -> Result<(u64, u64), EVMError<DB::Error>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("preverify", caller = ?self.data.env.tx.caller).entered();
+
         self.env().validate_block_env::<GSPEC, DB::Error>()?;
         self.env().validate_tx::<GSPEC>()?;
 
         let env = &self.data.env;
-        let tx_caller = env.tx.caller;
-        let tx_value = env.tx.value;
-        let tx_data = env.tx.data.clone();
-        let tx_gas_limit = env.tx.gas_limit;
         let tx_is_create = env.tx.transact_to.is_create();
-        let effective_gas_price = env.effective_gas_price();
 
-        let initial_gas_spend =
-            initial_tx_gas::<GSPEC>(&tx_data, tx_is_create, &env.tx.access_list);
+        let initial_gas_spend = match &env.cfg.gas_schedule {
+            Some(schedule) => initial_tx_gas_with::<GSPEC>(
+                schedule,
+                &env.tx.data,
+                tx_is_create,
+                &env.tx.access_list,
+            ),
+            None => initial_tx_gas::<GSPEC>(&env.tx.data, tx_is_create, &env.tx.access_list),
+        };
 
         // Additonal check to see if limit is big enought to cover initial gas.
         if env.tx.gas_limit < initial_gas_spend {
             return Err(InvalidTransaction::CallGasCostMoreThanGasLimit.into());
         }
 
-        // load coinbase
-        // EIP-3651: Warm COINBASE. Starts the `COINBASE` address warm
-        if GSPEC::enabled(SHANGHAI) {
-            self.data
-                .journaled_state
-                .initial_account_load(self.data.env.block.coinbase, &[], self.data.db)
-                .map_err(EVMError::Database)?;
-        }
-        self.load_access_list()?;
+        // coinbase and access list are loaded before the caller, so their slots are loaded too
+        // when the caller is one of them.
+        self.load_warm_accounts()?;
+
+        // load acc
+        let (caller_account, _) = self
+            .data
+            .journaled_state
+            .load_account(self.data.env.tx.caller, self.data.db)
+            .map_err(EVMError::Database)?;
+
+        // EIP-3607 exceptions need only the beginning of the sender code, it is read without
+        // loading the whole code.
+        let cfg = &self.data.env.cfg;
+        let prefix_len = cfg.max_sender_code_prefix_len();
+        let code_prefix = if prefix_len != 0
+            && !cfg.is_eip3607_disabled()
+            && caller_account.info.code.is_none()
+            && caller_account.info.code_hash != KECCAK_EMPTY
+        {
+            let code_hash = caller_account.info.code_hash;
+            Some(
+                self.data
+                    .db
+                    .code_prefix(code_hash, prefix_len)
+                    .map_err(EVMError::Database)?,
+            )
+        } else {
+            None
+        };
+
+        self.data
+            .env
+            .validate_tx_agains_state_with_code_prefix(caller_account, code_prefix.as_deref())?;
+
+        Ok((initial_gas_spend, caller_account.info.nonce))
+    }
+
+    /// Executes the validated transaction, caller account needs to be loaded.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn execute(&mut self, initial_gas_spend: u64, caller_nonce: u64) -> EVMResult<DB::Error> {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "transact",
+            caller = ?self.data.env.tx.caller,
+            nonce = caller_nonce,
+            gas_limit = self.data.env.tx.gas_limit,
+            gas_used = tracing::field::Empty,
+            accounts = tracing::field::Empty,
+        )
+        .entered();
+
+        let env = &self.data.env;
+        let tx_caller = env.tx.caller;
+        let tx_value = env.tx.value;
+        let tx_data = env.tx.data.clone();
+        let tx_gas_limit = env.tx.gas_limit;
+        let effective_gas_price = env.effective_gas_price();
 
         // load acc
         let journal = &mut self.data.journaled_state;
@@ -121,8 +289,6 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact<DB::Error>
             .load_account(tx_caller, self.data.db)
             .map_err(EVMError::Database)?;
 
-        self.data.env.validate_tx_agains_state(caller_account)?;
-
         // Reduce gas_limit*gas_price amount of caller account.
         // unwrap_or can only occur if disable_balance_check is enabled
         caller_account.info.balance = caller_account
@@ -193,33 +359,149 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact<DB::Error>
             }
         }
 
+        let created_contracts = self.data.journaled_state.take_created_contracts();
+        let selfdestructs = self.data.journaled_state.take_selfdestructs();
+        let cold_accesses = self.data.journaled_state.take_cold_accesses();
         let (state, logs, gas_used, gas_refunded) = self.finalize::<GSPEC>(&gas);
 
-        let result = match exit_reason.into() {
-            SuccessOrHalt::Success(reason) => ExecutionResult::Success {
-                reason,
-                gas_used,
-                gas_refunded,
-                logs,
-                output,
+        let result = self.execution_result(exit_reason, output, gas_used, gas_refunded, logs)?;
+
+        #[cfg(feature = "tracing")]
+        {
+            span.record("gas_used", result.gas_used());
+            span.record("accounts", state.len());
+        }
+
+        Ok(ResultAndState {
+            result,
+            state,
+            created_contracts,
+            selfdestructs,
+            cold_accesses,
+        })
+    }
+}
+
+impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> Transact<DB::Error>
+    for EVMImpl<'a, GSPEC, DB, INSPECT>
+{
+    fn transact(&mut self) -> EVMResult<DB::Error> {
+        let (initial_gas, caller_nonce) = self.validate_transaction()?;
+        self.execute(initial_gas, caller_nonce)
+    }
+
+    fn preverify_transaction(&mut self) -> Result<VerifiedTx, EVMError<DB::Error>> {
+        let (initial_gas, caller_nonce) = self.validate_transaction()?;
+
+        let env = &self.data.env;
+        Ok(VerifiedTx {
+            spec_id: GSPEC::SPEC_ID,
+            initial_gas,
+            caller_nonce,
+            caller: env.tx.caller,
+            gas_limit: env.tx.gas_limit,
+            gas_price: env.tx.gas_price,
+            gas_priority_fee: env.tx.gas_priority_fee,
+            transact_to: env.tx.transact_to.clone(),
+            value: env.tx.value,
+            data: env.tx.data.clone(),
+            chain_id: env.tx.chain_id,
+            nonce: env.tx.nonce,
+            access_list: env.tx.access_list.clone(),
+            basefee: env.block.basefee,
+            block_gas_limit: env.block.gas_limit,
+            cfg: env.cfg.clone(),
+        })
+    }
+
+    fn transact_preverified(&mut self, verified: VerifiedTx) -> EVMResult<DB::Error> {
+        if verified.spec_id != GSPEC::SPEC_ID || !verified.matches_env(self.data.env) {
+            return Err(EVMError::VerifiedTxMismatch);
+        }
+
+        let env = &self.data.env;
+        let tx_gas_limit = env.tx.gas_limit;
+        let max_cost = U256::from(tx_gas_limit)
+            .saturating_mul(env.tx.gas_price)
+            .saturating_add(env.tx.value);
+        let is_balance_check_disabled = env.cfg.is_balance_check_disabled();
+
+        // accounts are already loaded if the transaction was verified by this instance.
+        self.load_warm_accounts()?;
+
+        let (caller_account, _) = self
+            .data
+            .journaled_state
+            .load_account(self.data.env.tx.caller, self.data.db)
+            .map_err(EVMError::Database)?;
+        if caller_account.info.nonce != verified.caller_nonce {
+            return Err(EVMError::VerifiedTxMismatch);
+        }
+        // balance can change without a nonce change since the verification.
+        if !is_balance_check_disabled && max_cost > caller_account.info.balance {
+            return Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: tx_gas_limit,
+                balance: caller_account.info.balance,
+            }
+            .into());
+        }
+
+        self.execute(verified.initial_gas, verified.caller_nonce)
+    }
+
+    fn transact_system_call(&mut self, contract: B160) -> EVMResult<DB::Error> {
+        self.env().validate_block_env::<GSPEC, DB::Error>()?;
+
+        let caller = self.data.env.tx.caller;
+        let gas_limit = self.data.env.tx.gas_limit;
+        let data = self.data.env.tx.data.clone();
+
+        let (exit_reason, ret_gas, bytes) = self.call(&mut CallInputs {
+            contract,
+            transfer: Transfer {
+                source: caller,
+                target: contract,
+                value: U256::ZERO,
             },
-            SuccessOrHalt::Revert => ExecutionResult::Revert {
-                gas_used,
-                output: match output {
-                    Output::Call(return_value) => return_value,
-                    Output::Create(return_value, _) => return_value,
-                },
+            input: data,
+            gas_limit,
+            context: CallContext {
+                caller,
+                address: contract,
+                code_address: contract,
+                apparent_value: U256::ZERO,
+                scheme: CallScheme::Call,
             },
-            SuccessOrHalt::Halt(reason) => ExecutionResult::Halt { reason, gas_used },
-            SuccessOrHalt::FatalExternalError => {
-                return Err(EVMError::Database(self.data.error.take().unwrap()))
-            }
-            SuccessOrHalt::InternalContinue => {
-                panic!("Internal return flags should remain internal {exit_reason:?}")
-            }
-        };
+            is_static: false,
+        });
 
-        Ok(ResultAndState { result, state })
+        let mut gas = Gas::new(gas_limit);
+        gas.record_cost(gas_limit);
+        if crate::USE_GAS && matches!(exit_reason, return_ok!() | return_revert!()) {
+            gas.erase_cost(ret_gas.remaining());
+        }
+
+        let created_contracts = self.data.journaled_state.take_created_contracts();
+        let selfdestructs = self.data.journaled_state.take_selfdestructs();
+        let cold_accesses = self.data.journaled_state.take_cold_accesses();
+        let (mut state, logs) = self.data.journaled_state.finalize();
+
+        // EIP-161: caller is only touched by the zero value transfer.
+        if GSPEC::enabled(SPURIOUS_DRAGON) && state.get(&caller).map_or(false, |acc| acc.is_empty())
+        {
+            state.remove(&caller);
+        }
+
+        let result =
+            self.execution_result(exit_reason, Output::Call(bytes), gas.spend(), 0, logs)?;
+
+        Ok(ResultAndState {
+            result,
+            state,
+            created_contracts,
+            selfdestructs,
+            cold_accesses,
+        })
     }
 }
 
@@ -228,15 +510,13 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         db: &'a mut DB,
         env: &'a mut Env,
         inspector: &'a mut dyn Inspector<DB>,
-        precompiles: Precompiles,
+        precompiles: &'static Precompiles,
     ) -> Self {
-        let journaled_state = if GSPEC::enabled(SpecId::SPURIOUS_DRAGON) {
-            JournaledState::new(precompiles.len())
-        } else {
-            JournaledState::new_legacy(precompiles.len())
-        };
+        let journaled_state = JournaledState::new_with_spec(GSPEC::SPEC_ID, precompiles.len());
         Self {
             data: EVMData {
+                #[cfg(feature = "optional_instruction_limit")]
+                instructions_remaining: env.cfg.instruction_limit,
                 env,
                 journaled_state,
                 db,
@@ -248,50 +528,94 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         }
     }
 
+    fn execution_result(
+        &mut self,
+        exit_reason: InstructionResult,
+        output: Output,
+        gas_used: u64,
+        gas_refunded: u64,
+        logs: Vec<Log>,
+    ) -> Result<ExecutionResult, EVMError<DB::Error>> {
+        Ok(match exit_reason.into() {
+            SuccessOrHalt::Success(reason) => ExecutionResult::Success {
+                reason,
+                gas_used,
+                gas_refunded,
+                logs,
+                output,
+            },
+            SuccessOrHalt::Revert => ExecutionResult::Revert {
+                gas_used,
+                output: match output {
+                    Output::Call(return_value) => return_value,
+                    Output::Create(return_value, _) => return_value,
+                },
+            },
+            SuccessOrHalt::Halt(reason) => ExecutionResult::Halt { reason, gas_used },
+            SuccessOrHalt::FatalExternalError => {
+                return Err(EVMError::Database(self.data.error.take().unwrap()))
+            }
+            SuccessOrHalt::InternalContinue => {
+                panic!("Internal return flags should remain internal {exit_reason:?}")
+            }
+        })
+    }
+
     fn finalize<SPEC: Spec>(&mut self, gas: &Gas) -> (HashMap<B160, Account>, Vec<Log>, u64, u64) {
         let caller = self.data.env.tx.caller;
         let coinbase = self.data.env.block.coinbase;
         let (gas_used, gas_refunded) = if crate::USE_GAS {
-            let effective_gas_price = self.data.env.effective_gas_price();
-            let basefee = self.data.env.block.basefee;
-
             let gas_refunded = if self.env().cfg.is_gas_refund_disabled() {
                 0
             } else {
                 // EIP-3529: Reduction in refunds
-                let max_refund_quotient = if SPEC::enabled(LONDON) { 5 } else { 2 };
-                min(gas.refunded() as u64, gas.spend() / max_refund_quotient)
+                let max_refund_quotient = self
+                    .data
+                    .env
+                    .cfg
+                    .gas_schedule
+                    .map_or(SPEC::GAS_SCHEDULE.max_refund_quotient, |schedule| {
+                        schedule.max_refund_quotient
+                    });
+                // zero quotient of a custom schedule disables refunds.
+                let max_refund = gas.spend().checked_div(max_refund_quotient).unwrap_or(0);
+                min(gas.refunded() as u64, max_refund)
             };
 
+            let gas_used = gas.spend() - gas_refunded;
+            let fees = self
+                .data
+                .env
+                .calculate_fees_for_spec(SPEC::SPEC_ID, gas_used);
+
             // return balance of not spend gas.
             let caller_account = self.data.journaled_state.state().get_mut(&caller).unwrap();
             caller_account.info.balance = caller_account
                 .info
                 .balance
-                .saturating_add(effective_gas_price * U256::from(gas.remaining() + gas_refunded));
-
-            // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
-            let coinbase_gas_price = if SPEC::enabled(LONDON) {
-                effective_gas_price.saturating_sub(basefee)
-            } else {
-                effective_gas_price
-            };
+                .saturating_add(fees.refunded_to_sender);
 
-            // transfer fee to coinbase/beneficiary.
-            let Ok((coinbase_account, _)) = self
+            // transfer fee to coinbase/beneficiary, as defined by the reward policy.
+            let credits = self
                 .data
-                .journaled_state
-                .load_account(coinbase, self.data.db)
-            else {
-                panic!("coinbase account not found");
-            };
-            coinbase_account.mark_touch();
-            coinbase_account.info.balance = coinbase_account
-                .info
-                .balance
-                .saturating_add(coinbase_gas_price * U256::from(gas.spend() - gas_refunded));
+                .env
+                .cfg
+                .reward_policy
+                .credits(coinbase, fees.to_coinbase);
+            for (beneficiary, amount) in credits {
+                let Ok((beneficiary_account, _)) = self
+                    .data
+                    .journaled_state
+                    .load_account(beneficiary, self.data.db)
+                else {
+                    panic!("beneficiary account not found");
+                };
+                beneficiary_account.mark_touch();
+                beneficiary_account.info.balance =
+                    beneficiary_account.info.balance.saturating_add(amount);
+            }
 
-            (gas.spend() - gas_refunded, gas_refunded)
+            (gas_used, gas_refunded)
         } else {
             // touch coinbase
             let _ = self
@@ -402,6 +726,7 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         Ok(PreparedCreate {
             gas,
             created_address,
+            init_code_hash: code_hash,
             checkpoint,
             contract,
         })
@@ -442,13 +767,7 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                 // EIP-170: Contract code size limit
                 // By default limit is 0x6000 (~25kb)
                 if GSPEC::enabled(SPURIOUS_DRAGON)
-                    && bytes.len()
-                        > self
-                            .data
-                            .env
-                            .cfg
-                            .limit_contract_code_size
-                            .unwrap_or(MAX_CODE_SIZE)
+                    && bytes.len() > self.data.env.cfg.max_code_size()
                 {
                     self.data
                         .journaled_state
@@ -490,9 +809,20 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
                     AnalysisKind::Check => Bytecode::new_raw(bytes.clone()).to_checked(),
                     AnalysisKind::Analyse => to_analysed(Bytecode::new_raw(bytes.clone())),
                 };
+                let code_hash = bytecode.hash();
                 self.data
                     .journaled_state
                     .set_code(prepared_create.created_address, bytecode);
+                self.data
+                    .journaled_state
+                    .created_contracts
+                    .push(CreatedContract {
+                        address: prepared_create.created_address,
+                        deployer: inputs.caller,
+                        init_code_hash: prepared_create.init_code_hash,
+                        code_hash,
+                        destroyed: false,
+                    });
                 CreateResult {
                     result: InstructionResult::Return,
                     created_address: Some(prepared_create.created_address),
@@ -534,6 +864,22 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
         #[cfg(not(feature = "memory_limit"))]
         let mut interpreter = Box::new(Interpreter::new(contract, gas_limit, is_static));
 
+        interpreter.gas_schedule = self.data.env.cfg.gas_schedule;
+        interpreter.eip_overrides = self.data.env.cfg.eip_overrides;
+
+        // only the top frame gets a span, nested calls are part of it.
+        #[cfg(feature = "tracing")]
+        let span = (self.data.journaled_state.depth() == 1).then(|| {
+            tracing::debug_span!(
+                "interpreter",
+                address = ?interpreter.contract.address,
+                gas_limit,
+                is_static,
+                gas_used = tracing::field::Empty,
+            )
+            .entered()
+        });
+
         if INSPECT {
             self.inspector
                 .initialize_interp(&mut interpreter, &mut self.data);
@@ -544,6 +890,11 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
             interpreter.run::<Self, GSPEC>(self)
         };
 
+        #[cfg(feature = "tracing")]
+        if let Some(span) = span {
+            span.record("gas_used", interpreter.gas.spend());
+        }
+
         (exit_reason, interpreter)
     }
 
@@ -556,10 +907,33 @@ impl<'a, GSPEC: Spec, DB: Database, const INSPECT: bool> EVMImpl<'a, GSPEC, DB,
             .precompiles
             .get(&contract)
             .expect("Check for precompile should be already done");
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "precompile",
+            address = ?contract,
+            gas_limit = gas.limit(),
+            gas_used = tracing::field::Empty,
+        )
+        .entered();
+        if INSPECT {
+            self.inspector
+                .precompile_call(contract, &input_data, gas.limit());
+        }
         let out = match precompile {
             Precompile::Standard(fun) => fun(&input_data, gas.limit()),
             Precompile::Custom(fun) => fun(&input_data, gas.limit()),
         };
+        if INSPECT {
+            let gas_used = match out {
+                Ok((gas_used, _)) if gas_used <= gas.limit() => gas_used,
+                _ => gas.limit(),
+            };
+            self.inspector.precompile_call_end(contract, &out, gas_used);
+        }
+        #[cfg(feature = "tracing")]
+        if let Ok((gas_used, _)) = out {
+            span.record("gas_used", gas_used);
+        }
         match out {
             Ok((gas_used, data)) => {
                 if !crate::USE_GAS || gas.record_cost(gas_used) {
@@ -703,6 +1077,19 @@ impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
         self.inspector.step_end(interp, &mut self.data, ret)
     }
 
+    #[cfg(feature = "optional_instruction_limit")]
+    #[inline(always)]
+    fn consume_instruction(&mut self) -> bool {
+        match &mut self.data.instructions_remaining {
+            None => true,
+            Some(0) => false,
+            Some(remaining) => {
+                *remaining -= 1;
+                true
+            }
+        }
+    }
+
     fn env(&mut self) -> &mut Env {
         self.data.env
     }
@@ -752,8 +1139,9 @@ impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
         let db = &mut self.data.db;
         let error = &mut self.data.error;
 
+        // only the hash is needed, bytecode is not loaded.
         let (acc, is_cold) = journal
-            .load_code(address, db)
+            .load_account(address, db)
             .map_err(|e| *error = Some(e))
             .ok()?;
 
@@ -855,4 +1243,1163 @@ impl<'a, GSPEC: Spec, DB: Database + 'a, const INSPECT: bool> Host
             (ret.result, ret.gas, ret.return_value)
         }
     }
+
+    fn record_cold_access(&mut self, access: ColdAccess) {
+        if self.data.env.cfg.record_cold_accesses {
+            self.data.journaled_state.cold_accesses.push(access);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::InMemoryDB;
+    use crate::primitives::{
+        create_address, hex_literal::hex, keccak256, AccountInfo, Bytecode, Bytes, ColdAccess,
+        CreatedContract, EVMError, ExecutionResult, GasSchedule, Halt, InvalidTransaction, Output,
+        ResultAndState, RewardPolicy, SelfdestructEvent, SpecId, State, TransactTo, B160,
+        KECCAK_EMPTY, U256,
+    };
+    use crate::EVM;
+
+    /// Returns EVM over `db` with a transaction to `transact_to` limited to `gas_limit`.
+    fn test_evm(db: InMemoryDB, transact_to: TransactTo, gas_limit: u64) -> EVM<InMemoryDB> {
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.transact_to = transact_to;
+        evm.env.tx.gas_limit = gas_limit;
+        evm
+    }
+
+    #[test]
+    fn contract_code_size_limit() {
+        // PUSH2 30000, PUSH1 0, RETURN: deploys 30000 zero bytes.
+        let init_code = Bytes::from_static(&hex!("6175306000f3"));
+
+        let mut evm = test_evm(InMemoryDB::default(), TransactTo::create(), 10_000_000);
+        evm.env.tx.data = init_code.clone();
+        let result = evm.transact().unwrap().result;
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: Halt::CreateContractSizeLimit,
+                gas_used: 10_000_000
+            }
+        );
+
+        for limit in [0x8000, 0] {
+            let mut evm = test_evm(InMemoryDB::default(), TransactTo::create(), 10_000_000);
+            evm.env.tx.data = init_code.clone();
+            evm.env.cfg.limit_contract_code_size = Some(limit);
+            let ResultAndState { result, state, .. } = evm.transact().unwrap();
+            let ExecutionResult::Success {
+                gas_used,
+                output: Output::Create(_, Some(address)),
+                ..
+            } = result
+            else {
+                panic!("create should succeed: {result:?}");
+            };
+            // intrinsic 53000 + calldata 84 + initcode 2 + execution with memory 4538 + deposit 6_000_000
+            assert_eq!(gas_used, 6_057_624);
+            assert_eq!(state[&address].info.code.as_ref().unwrap().len(), 30_000);
+        }
+    }
+
+    #[test]
+    fn initcode_size_limit() {
+        // PUSH1 0, PUSH1 0, RETURN padded with zeros.
+        let mut init_code = hex!("60006000f3").to_vec();
+        init_code.resize(50_000, 0);
+        let init_code = Bytes::from(init_code);
+
+        let mut evm = test_evm(InMemoryDB::default(), TransactTo::create(), 10_000_000);
+        evm.env.tx.data = init_code.clone();
+        assert_eq!(
+            evm.transact(),
+            Err(EVMError::Transaction(
+                InvalidTransaction::CreateInitcodeSizeLimit
+            ))
+        );
+
+        for limit in [60_000, 0] {
+            let mut evm = test_evm(InMemoryDB::default(), TransactTo::create(), 10_000_000);
+            evm.env.tx.data = init_code.clone();
+            evm.env.cfg.limit_initcode_size = Some(limit);
+            let result = evm.transact().unwrap().result;
+            // intrinsic 53000 + calldata 200_036 + initcode 3126 + execution 6
+            assert!(
+                matches!(result, ExecutionResult::Success { gas_used, .. } if gas_used == 256_168),
+                "{result:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "optional_code_size_limit")]
+    #[test]
+    fn disable_code_size_limit() {
+        // PUSH2 30000, PUSH1 0, RETURN: deploys 30000 zero bytes.
+        let init_code = Bytes::from_static(&hex!("6175306000f3"));
+
+        let mut evm = test_evm(InMemoryDB::default(), TransactTo::create(), 10_000_000);
+        evm.env.tx.data = init_code.clone();
+        assert!(matches!(
+            evm.transact().unwrap().result,
+            ExecutionResult::Halt {
+                reason: Halt::CreateContractSizeLimit,
+                ..
+            }
+        ));
+
+        // limits that are set are ignored too.
+        let mut evm = test_evm(InMemoryDB::default(), TransactTo::create(), 10_000_000);
+        evm.env.tx.data = init_code;
+        evm.env.cfg.limit_contract_code_size = Some(0x6000);
+        evm.env.cfg.disable_code_size_limit = true;
+        let ResultAndState { result, state, .. } = evm.transact().unwrap();
+        let ExecutionResult::Success {
+            gas_used,
+            output: Output::Create(_, Some(address)),
+            ..
+        } = result
+        else {
+            panic!("create should succeed: {result:?}");
+        };
+        // code deposit is still charged.
+        assert_eq!(gas_used, 6_057_624);
+        assert_eq!(state[&address].info.code.as_ref().unwrap().len(), 30_000);
+
+        // initcode over 2 * MAX_CODE_SIZE.
+        let mut init_code = hex!("60006000f3").to_vec();
+        init_code.resize(50_000, 0);
+        let mut evm = test_evm(InMemoryDB::default(), TransactTo::create(), 10_000_000);
+        evm.env.tx.data = Bytes::from(init_code);
+        evm.env.cfg.disable_code_size_limit = true;
+        assert!(evm.transact().unwrap().result.is_success());
+    }
+
+    #[test]
+    fn created_contracts() {
+        let factory = B160::from(0x1000);
+        // CREATE with init code `00`,
+        // CREATE with init code `60006000fd` that reverts,
+        // CREATE with init code `33ff` that selfdestructs.
+        let factory_code = Bytes::from_static(&hex!(
+            "600160006000f050 6460006000fd600052 6005601b6000f050 6133ff600052 6002601e6000f050 00"
+        ));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            factory,
+            AccountInfo {
+                nonce: 1,
+                code: Some(Bytecode::new_raw(factory_code)),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = test_evm(db, TransactTo::Call(factory), 1_000_000);
+        let ResultAndState {
+            result,
+            created_contracts,
+            ..
+        } = evm.transact().unwrap();
+        assert!(result.is_success(), "{result:?}");
+        assert_eq!(
+            created_contracts,
+            vec![
+                CreatedContract {
+                    address: create_address(factory, 1),
+                    deployer: factory,
+                    init_code_hash: keccak256(&hex!("00")),
+                    code_hash: KECCAK_EMPTY,
+                    destroyed: false,
+                },
+                // nonce 2 was used by the reverted creation.
+                CreatedContract {
+                    address: create_address(factory, 3),
+                    deployer: factory,
+                    init_code_hash: keccak256(&hex!("33ff")),
+                    code_hash: KECCAK_EMPTY,
+                    destroyed: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn split_code_database() {
+        use crate::db::{CacheDB, DatabaseRef, WrapDatabaseSplitCode};
+        use crate::interpreter::opcode;
+        use crate::primitives::{HashMap, B256};
+        use core::cell::RefCell;
+        use core::convert::Infallible;
+
+        /// Returns code from `basic` and counts `code_by_hash` calls per hash.
+        #[derive(Default)]
+        struct CodeCountingDB {
+            codes: HashMap<B160, Bytecode>,
+            code_fetches: RefCell<HashMap<B256, usize>>,
+        }
+
+        impl DatabaseRef for CodeCountingDB {
+            type Error = Infallible;
+
+            fn basic(&self, address: B160) -> Result<Option<AccountInfo>, Self::Error> {
+                Ok(self.codes.get(&address).map(|code| AccountInfo {
+                    code_hash: code.hash(),
+                    code: Some(code.clone()),
+                    ..Default::default()
+                }))
+            }
+
+            fn code_by_hash(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+                *self.code_fetches.borrow_mut().entry(code_hash).or_default() += 1;
+                Ok(self
+                    .codes
+                    .values()
+                    .find(|code| code.hash() == code_hash)
+                    .cloned()
+                    .unwrap_or_default())
+            }
+
+            fn storage(&self, _address: B160, _index: U256) -> Result<U256, Self::Error> {
+                Ok(U256::ZERO)
+            }
+
+            fn block_hash(&self, _number: U256) -> Result<B256, Self::Error> {
+                Ok(B256::zero())
+            }
+        }
+
+        let (a, b, c, d) = (
+            B160::from(0xa),
+            B160::from(0xb),
+            B160::from(0xc),
+            B160::from(0xd),
+        );
+        // EXTCODESIZE of `c` and `b`, EXTCODEHASH of `d`.
+        let mut code = Vec::new();
+        for (address, op) in [
+            (c, opcode::EXTCODESIZE),
+            (b, opcode::EXTCODESIZE),
+            (d, opcode::EXTCODEHASH),
+        ] {
+            code.push(opcode::PUSH20);
+            code.extend_from_slice(address.as_bytes());
+            code.extend_from_slice(&[op, opcode::POP]);
+        }
+        code.push(opcode::STOP);
+        let code = Bytecode::new_raw(code.into());
+
+        let mut db = CodeCountingDB::default();
+        // `a` and `c` share the same bytecode.
+        db.codes.insert(a, code.clone());
+        db.codes.insert(c, code.clone());
+        db.codes
+            .insert(b, Bytecode::new_raw(Bytes::from_static(&[opcode::STOP])));
+        db.codes
+            .insert(d, Bytecode::new_raw(Bytes::from_static(&[opcode::INVALID])));
+
+        let mut evm = crate::new();
+        evm.database(CacheDB::new(WrapDatabaseSplitCode::new(db)));
+        evm.env.tx.gas_limit = 100_000;
+        for target in [a, c] {
+            evm.env.tx.transact_to = TransactTo::Call(target);
+            let result = evm.transact_commit().unwrap();
+            assert!(result.is_success(), "{result:?}");
+        }
+
+        let fetches = evm.db.unwrap().db.db.code_fetches.into_inner();
+        assert_eq!(fetches.len(), 2);
+        assert_eq!(fetches[&code.hash()], 1);
+        assert_eq!(fetches[&keccak256(&[opcode::STOP])], 1);
+    }
+
+    #[test]
+    fn extcodecopy_window() {
+        use crate::interpreter::opcode;
+
+        let (target, other) = (B160::from(0x1000), B160::from(0x2000));
+        for (code_offset, len, expected) in [
+            (U256::ZERO, 4, hex!("01020304")),
+            (U256::from(2), 4, hex!("03040000")),
+            (U256::from(10), 3, hex!("000000ff")),
+            (U256::MAX, 4, hex!("00000000")),
+        ] {
+            // MSTORE(0, 0xff..ff), EXTCODECOPY(other, 0, code_offset, len), RETURN(0, 32).
+            let mut code = vec![opcode::PUSH32];
+            code.extend_from_slice(&[0xff; 32]);
+            code.extend_from_slice(&[opcode::PUSH1, 0, opcode::MSTORE, opcode::PUSH1, len]);
+            code.push(opcode::PUSH32);
+            code.extend_from_slice(&code_offset.to_be_bytes::<32>());
+            code.extend_from_slice(&[opcode::PUSH1, 0, opcode::PUSH20]);
+            code.extend_from_slice(other.as_bytes());
+            code.push(opcode::EXTCODECOPY);
+            code.extend_from_slice(&[opcode::PUSH1, 32, opcode::PUSH1, 0, opcode::RETURN]);
+
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                target,
+                AccountInfo::new(U256::ZERO, 1, Bytecode::new_raw(code.into())),
+            );
+            db.insert_account_info(
+                other,
+                AccountInfo::new(
+                    U256::ZERO,
+                    1,
+                    Bytecode::new_raw(Bytes::from_static(&hex!("01020304"))).to_checked(),
+                ),
+            );
+
+            let mut evm = test_evm(db, TransactTo::Call(target), 100_000);
+            let result = evm.transact().unwrap().result;
+            assert!(result.is_success(), "{result:?}");
+
+            let output = result.into_output().unwrap();
+            assert_eq!(output[..4], expected);
+            assert_eq!(output[4..], [0xff; 28]);
+        }
+    }
+
+    /// Returns gas used by a contract that calls `to` with `value` `times` times, with zero gas
+    /// limit for the callee.
+    fn value_call_gas(
+        spec_id: SpecId,
+        to_info: Option<AccountInfo>,
+        value: u8,
+        times: usize,
+    ) -> u64 {
+        let (target, to) = (B160::from(0x1000), B160::from(0x2000));
+        let mut code = Vec::new();
+        for _ in 0..times {
+            // CALL(0, to, value, 0, 0, 0, 0), POP
+            code.extend_from_slice(&hex!("6000600060006000 60"));
+            code.push(value);
+            code.push(0x73);
+            code.extend_from_slice(to.as_bytes());
+            code.extend_from_slice(&hex!("6000 f1 50"));
+        }
+        code.push(0x00);
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            target,
+            AccountInfo::new(U256::from(1000), 1, Bytecode::new_raw(code.into())),
+        );
+        if let Some(info) = to_info {
+            db.insert_account_info(to, info);
+        }
+
+        let mut evm = test_evm(db, TransactTo::Call(target), 1_000_000);
+        evm.env.cfg.spec_id = spec_id;
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success(), "{result:?}");
+        result.gas_used()
+    }
+
+    #[test]
+    fn call_new_account_cost() {
+        let existing = || {
+            Some(AccountInfo {
+                nonce: 1,
+                ..Default::default()
+            })
+        };
+        let empty = || Some(AccountInfo::default());
+
+        // (spec, target account, value, number of calls, new account surcharge)
+        let cases = [
+            // value call to non existing account is always charged.
+            (SpecId::HOMESTEAD, None, 1, 1, 25_000),
+            (SpecId::LATEST, None, 1, 1, 25_000),
+            // empty account exists before EIP-161 and is treated as new after it.
+            (SpecId::HOMESTEAD, empty(), 1, 1, 0),
+            (SpecId::LATEST, empty(), 1, 1, 25_000),
+            // zero value call is charged only before EIP-161.
+            (SpecId::HOMESTEAD, None, 0, 1, 25_000),
+            (SpecId::HOMESTEAD, empty(), 0, 1, 0),
+            (SpecId::LATEST, None, 0, 1, 0),
+            // first call creates the account, following calls are not charged.
+            (SpecId::HOMESTEAD, None, 1, 3, 25_000),
+            (SpecId::HOMESTEAD, None, 0, 3, 25_000),
+            (SpecId::LATEST, None, 1, 3, 25_000),
+            (SpecId::LATEST, empty(), 1, 3, 25_000),
+            // zero value call after EIP-161 does not make the account non empty.
+            (SpecId::LATEST, None, 0, 3, 0),
+        ];
+        for (spec_id, to_info, value, times, surcharge) in cases {
+            let baseline = value_call_gas(spec_id, existing(), value, times);
+            assert_eq!(
+                value_call_gas(spec_id, to_info.clone(), value, times),
+                baseline + surcharge,
+                "{spec_id:?} {to_info:?} value {value} times {times}"
+            );
+        }
+    }
+
+    fn gas_refunded(mut evm: EVM<InMemoryDB>) -> u64 {
+        match evm.transact().unwrap().result {
+            ExecutionResult::Success { gas_refunded, .. } => gas_refunded,
+            result => panic!("transaction should succeed: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn refund_of_reverted_call() {
+        let (caller, callee) = (B160::from(0x1000), B160::from(0x2000));
+        // CALL(gas, callee, 0, 0, 0, 0, 0), POP, STOP.
+        let caller_code = Bytes::from_static(&hex!(
+            "60006000600060006000 73 0000000000000000000000000000000000002000 5a f1 50 00"
+        ));
+        // clear slot 0 and either stop or revert.
+        for (callee_code, expected_refund) in [
+            (&hex!("6000600055 00")[..], 4800),
+            (&hex!("6000600055 60006000fd")[..], 0),
+        ] {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                caller,
+                AccountInfo::new(
+                    Default::default(),
+                    1,
+                    Bytecode::new_raw(caller_code.clone()),
+                ),
+            );
+            db.insert_account_info(
+                callee,
+                AccountInfo::new(
+                    Default::default(),
+                    1,
+                    Bytecode::new_raw(Bytes::copy_from_slice(callee_code)),
+                ),
+            );
+            db.insert_account_storage(callee, U256::ZERO, U256::from(1))
+                .unwrap();
+
+            assert_eq!(
+                gas_refunded(test_evm(db, TransactTo::Call(caller), 1_000_000)),
+                expected_refund
+            );
+        }
+    }
+
+    #[test]
+    fn refund_of_failed_create() {
+        let factory = B160::from(0x1000);
+        // Both init codes set slot 0 to 1 and back to 0, which is refunded.
+        // First one stops, second one loops until it runs out of gas.
+        // Init code is stored in memory and deployed with CREATE, result is popped.
+        let factories = [
+            hex!("6a 6001600055600060005500 600052 600b 6015 6000 f0 50 00").to_vec(),
+            hex!("6d 60016000556000600055 5b600a56 600052 600e 6012 6000 f0 50 00").to_vec(),
+        ];
+        let mut refunds = Vec::new();
+        for factory_code in factories {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                factory,
+                AccountInfo::new(
+                    Default::default(),
+                    1,
+                    Bytecode::new_raw(factory_code.into()),
+                ),
+            );
+            refunds.push(gas_refunded(test_evm(
+                db,
+                TransactTo::Call(factory),
+                1_000_000,
+            )));
+        }
+        assert!(refunds[0] > 0);
+        // refund accrued in the failed frame is discarded together with its state.
+        assert_eq!(refunds[1], 0);
+    }
+
+    #[test]
+    fn reward_policy() {
+        let (caller, coinbase) = (B160::from(0x1000), B160::from(0xc0));
+        let transfer_with_reward_policy = |reward_policy: RewardPolicy| {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(caller, AccountInfo::from_balance(U256::from(1_000_000)));
+            let mut evm = test_evm(db, TransactTo::Call(B160::from(0x2000)), 21_000);
+            evm.env.cfg.reward_policy = reward_policy;
+            evm.env.block.coinbase = coinbase;
+            evm.env.tx.caller = caller;
+            evm.env.tx.gas_price = U256::from(1);
+            evm.transact()
+        };
+        let balance =
+            |state: &State, address: B160| state.get(&address).map(|acc| acc.info.balance);
+
+        let ResultAndState { result, state, .. } =
+            transfer_with_reward_policy(RewardPolicy::Coinbase).unwrap();
+        assert_eq!(result.gas_used(), 21_000);
+        assert_eq!(balance(&state, coinbase), Some(U256::from(21_000)));
+
+        // burned fee is still paid by the caller.
+        let ResultAndState { result, state, .. } =
+            transfer_with_reward_policy(RewardPolicy::Burn).unwrap();
+        assert_eq!(result.gas_used(), 21_000);
+        assert!(!state.get(&coinbase).map_or(false, |acc| acc.is_touched()));
+        assert_eq!(
+            balance(&state, caller),
+            Some(U256::from(1_000_000 - 21_000))
+        );
+
+        // 33.33% of 21000 is 6999.3 and 66.67% is 14000.7, remainder goes to the first address.
+        let (first, second) = (B160::from(0xa), B160::from(0xb));
+        let ResultAndState { result, state, .. } =
+            transfer_with_reward_policy(RewardPolicy::Split(vec![(first, 3333), (second, 6667)]))
+                .unwrap();
+        assert_eq!(result.gas_used(), 21_000);
+        assert_eq!(balance(&state, first), Some(U256::from(7_000)));
+        assert_eq!(balance(&state, second), Some(U256::from(14_000)));
+        assert!(!state.get(&coinbase).map_or(false, |acc| acc.is_touched()));
+
+        // shares over 10000 bps would credit more than the fee.
+        assert_eq!(
+            transfer_with_reward_policy(RewardPolicy::Split(vec![(first, 6000), (second, 5000)])),
+            Err(EVMError::InvalidRewardPolicy)
+        );
+    }
+
+    #[test]
+    fn push0_override() {
+        let run = |spec_id: SpecId, push0: Option<bool>| {
+            let target = B160::from(0x1000);
+            let mut db = InMemoryDB::default();
+            // PUSH0, STOP
+            db.insert_account_info(
+                target,
+                AccountInfo::new(
+                    U256::ZERO,
+                    1,
+                    Bytecode::new_raw(Bytes::from_static(&hex!("5f 00"))),
+                ),
+            );
+
+            let mut evm = test_evm(db, TransactTo::Call(target), 100_000);
+            evm.env.cfg.spec_id = spec_id;
+            evm.env.cfg.eip_overrides.eip3855_push0 = push0;
+            evm.transact().unwrap().result
+        };
+
+        let not_activated = ExecutionResult::Halt {
+            reason: Halt::NotActivated,
+            gas_used: 100_000,
+        };
+        assert_eq!(run(SpecId::MERGE, None), not_activated);
+        assert_eq!(run(SpecId::SHANGHAI, Some(false)), not_activated);
+        assert!(run(SpecId::SHANGHAI, None).is_success());
+        assert!(run(SpecId::MERGE, Some(true)).is_success());
+    }
+
+    #[test]
+    fn warm_coinbase() {
+        let contract = B160::from(0x1000);
+        let gas_used = |spec_id: SpecId| {
+            let mut db = InMemoryDB::default();
+            // COINBASE, BALANCE, POP, STOP
+            db.insert_account_info(
+                contract,
+                AccountInfo::new(
+                    U256::ZERO,
+                    1,
+                    Bytecode::new_raw(Bytes::from_static(&hex!("41 31 50 00"))),
+                ),
+            );
+            let mut evm = test_evm(db, TransactTo::Call(contract), 100_000);
+            evm.env.cfg.spec_id = spec_id;
+            evm.env.block.coinbase = B160::from(0xc0);
+            evm.transact().unwrap().result.gas_used()
+        };
+        let merge = gas_used(SpecId::MERGE);
+        let shanghai = gas_used(SpecId::SHANGHAI);
+        // intrinsic 21000 + COINBASE 2 + BALANCE + POP 2
+        assert_eq!(merge, 21_000 + 2 + 2600 + 2);
+        assert_eq!(shanghai, 21_000 + 2 + 100 + 2);
+    }
+
+    #[test]
+    fn access_list_slots_of_loaded_account() {
+        let contract = B160::from(0x1000);
+        let gas_used = |coinbase: B160, access_list: Vec<(B160, Vec<U256>)>| {
+            let mut db = InMemoryDB::default();
+            // SLOAD(1), POP, STOP
+            db.insert_account_info(
+                contract,
+                AccountInfo::new(
+                    U256::ZERO,
+                    1,
+                    Bytecode::new_raw(Bytes::from_static(&hex!("6001 54 50 00"))),
+                ),
+            );
+            let mut evm = test_evm(db, TransactTo::Call(contract), 100_000);
+            evm.env.cfg.spec_id = SpecId::SHANGHAI;
+            evm.env.block.coinbase = coinbase;
+            evm.env.tx.access_list = access_list;
+            evm.transact().unwrap().result.gas_used()
+        };
+        // intrinsic 21000 + access list, PUSH1 3, warm SLOAD 100, POP 2.
+        let warm_slot = 21_000 + 2400 * 2 + 1900 + 3 + 100 + 2;
+
+        // slots of the warm coinbase are loaded from the access list.
+        assert_eq!(
+            gas_used(
+                contract,
+                vec![
+                    (contract, vec![U256::from(1)]),
+                    (B160::from(0x2000), vec![])
+                ]
+            ),
+            warm_slot
+        );
+        // address listed twice, slots of the second entry are loaded too.
+        assert_eq!(
+            gas_used(
+                B160::from(0xc0),
+                vec![(contract, vec![]), (contract, vec![U256::from(1)])]
+            ),
+            warm_slot
+        );
+    }
+
+    #[cfg(feature = "optional_warm_injection")]
+    #[test]
+    fn injected_warm_accounts() {
+        let contract = B160::from(0x1000);
+        let mut db = InMemoryDB::default();
+        // SLOAD(1), POP, BALANCE(0x3000), POP, STOP
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(
+                U256::ZERO,
+                1,
+                Bytecode::new_raw(Bytes::from_static(&hex!("6001 54 50 613000 31 50 00"))),
+            ),
+        );
+        let mut evm = test_evm(db, TransactTo::Call(contract), 100_000);
+        let cold = evm.transact().unwrap().result.gas_used();
+        evm.env.tx.warm_addresses = vec![B160::from(0x3000)];
+        evm.env.tx.warm_storage = vec![(contract, U256::from(1))];
+        let warm = evm.transact().unwrap().result.gas_used();
+        // no intrinsic gas is charged for injected accounts and slots.
+        assert_eq!(cold, 21_000 + 3 + 2100 + 2 + 3 + 2600 + 2);
+        assert_eq!(warm, 21_000 + 3 + 100 + 2 + 3 + 100 + 2);
+    }
+
+    #[test]
+    fn fees_match_balances() {
+        let (caller, coinbase) = (B160::from(0x1000), B160::from(0xc0));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(10_000_000)));
+
+        let mut evm = test_evm(db, TransactTo::Call(B160::from(0x2000)), 30_000);
+        evm.env.block.coinbase = coinbase;
+        evm.env.block.basefee = U256::from(7);
+        evm.env.tx.caller = caller;
+        evm.env.tx.gas_price = U256::from(10);
+        evm.env.tx.gas_priority_fee = Some(U256::from(2));
+        let ResultAndState { result, state, .. } = evm.transact().unwrap();
+
+        let fees = evm.env.calculate_fees(result.gas_used());
+        assert_eq!(fees.paid_by_sender, U256::from(21_000 * 9));
+        assert_eq!(fees.refunded_to_sender, U256::from(9_000 * 9));
+        assert_eq!(
+            state[&caller].info.balance,
+            U256::from(10_000_000) - fees.paid_by_sender
+        );
+        assert_eq!(state[&coinbase].info.balance, fees.to_coinbase);
+        assert_eq!(fees.burned, U256::from(21_000 * 7));
+    }
+
+    #[test]
+    fn selfdestructs() {
+        let (a, b, c, d) = (
+            B160::from(0x1000),
+            B160::from(0x2000),
+            B160::from(0x3000),
+            B160::from(0x4000),
+        );
+        // `a` calls `b`, `b` calls `c` and either stops or reverts, `c` selfdestructs to `d`.
+        let a_code =
+            hex!("60006000600060006000 73 0000000000000000000000000000000000002000 5a f1 50 00");
+        let b_code =
+            hex!("60006000600060006000 73 0000000000000000000000000000000000003000 5a f1 50");
+        let c_code = hex!("73 0000000000000000000000000000000000004000 ff");
+
+        for (b_end, reverted) in [(&hex!("00")[..], false), (&hex!("60006000fd")[..], true)] {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                a,
+                AccountInfo::new(
+                    U256::ZERO,
+                    1,
+                    Bytecode::new_raw(Bytes::from_static(&a_code)),
+                ),
+            );
+            db.insert_account_info(
+                b,
+                AccountInfo::new(
+                    U256::ZERO,
+                    1,
+                    Bytecode::new_raw([&b_code[..], b_end].concat().into()),
+                ),
+            );
+            db.insert_account_info(
+                c,
+                AccountInfo::new(
+                    U256::from(100),
+                    1,
+                    Bytecode::new_raw(Bytes::from_static(&c_code)),
+                ),
+            );
+
+            let mut evm = test_evm(db, TransactTo::Call(a), 1_000_000);
+            let ResultAndState {
+                result,
+                state,
+                selfdestructs,
+                ..
+            } = evm.transact().unwrap();
+            assert!(result.is_success(), "{result:?}");
+
+            let balance = |address| state.get(&address).map(|acc| acc.info.balance);
+            if reverted {
+                assert_eq!(selfdestructs, vec![]);
+                assert_eq!(balance(c), Some(U256::from(100)));
+                assert_eq!(balance(d).unwrap_or_default(), U256::ZERO);
+            } else {
+                assert_eq!(
+                    selfdestructs,
+                    vec![SelfdestructEvent {
+                        contract: c,
+                        beneficiary: d,
+                        value: U256::from(100),
+                    }]
+                );
+                assert_eq!(balance(d), Some(U256::from(100)));
+            }
+        }
+
+        // ADDRESS, SELFDESTRUCT: balance is burned.
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            c,
+            AccountInfo::new(
+                U256::from(100),
+                1,
+                Bytecode::new_raw(Bytes::from_static(&hex!("30ff"))),
+            ),
+        );
+        let mut evm = test_evm(db, TransactTo::Call(c), 1_000_000);
+        let ResultAndState {
+            state,
+            selfdestructs,
+            ..
+        } = evm.transact().unwrap();
+        assert_eq!(
+            selfdestructs,
+            vec![SelfdestructEvent {
+                contract: c,
+                beneficiary: c,
+                value: U256::from(100),
+            }]
+        );
+        assert_eq!(state[&c].info.balance, U256::ZERO);
+    }
+
+    #[cfg(feature = "optional_instruction_limit")]
+    #[test]
+    fn instruction_limit() {
+        use crate::interpreter::{InstructionResult, Interpreter};
+        use crate::{Database, EVMData, Inspector};
+
+        /// Counts executed steps and records remaining instruction budget.
+        #[derive(Default)]
+        struct StepCounter {
+            steps: u64,
+            remaining: Option<u64>,
+        }
+
+        impl<DB: Database> Inspector<DB> for StepCounter {
+            fn step(
+                &mut self,
+                _interp: &mut Interpreter,
+                data: &mut EVMData<'_, DB>,
+            ) -> InstructionResult {
+                self.steps += 1;
+                self.remaining = data.instructions_remaining;
+                InstructionResult::Continue
+            }
+        }
+
+        let contract = B160::from(0x1000);
+        let call = |code: &'static [u8], limit: Option<u64>, counter: &mut StepCounter| {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                contract,
+                AccountInfo::new(U256::ZERO, 1, Bytecode::new_raw(Bytes::from_static(code))),
+            );
+            let mut evm = test_evm(db, TransactTo::Call(contract), 1_000_000);
+            evm.env.cfg.instruction_limit = limit;
+            evm.inspect(counter).unwrap()
+        };
+
+        // JUMPDEST, PUSH1 0, JUMP: infinite loop.
+        let mut counter = StepCounter::default();
+        let ResultAndState { result, .. } = call(&hex!("5b600056"), Some(10), &mut counter);
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: Halt::InstructionLimitReached,
+                gas_used: 1_000_000
+            }
+        );
+        assert_eq!(counter.steps, 10);
+        assert_eq!(counter.remaining, Some(0));
+
+        // PUSH1 1, PUSH1 1, ADD, POP, STOP: five instructions.
+        let code = &hex!("6001600101 50 00");
+        let unlimited = call(code, None, &mut StepCounter::default());
+        assert!(unlimited.result.is_success());
+        assert_eq!(call(code, Some(5), &mut StepCounter::default()), unlimited);
+        assert_eq!(
+            call(code, Some(1_000), &mut StepCounter::default()),
+            unlimited
+        );
+        assert!(!call(code, Some(4), &mut StepCounter::default())
+            .result
+            .is_success());
+    }
+
+    #[test]
+    fn cold_accesses() {
+        use crate::interpreter::opcode;
+
+        let contract = B160::from(0x1000);
+        let code = Bytes::from_static(&hex!(
+            // SLOAD(1) twice
+            "600154 50 600154 50"
+            // EXTCODESIZE(0x2000)
+            "73 0000000000000000000000000000000000002000 3b 50"
+            // CALL(gas, 0x2000, 0, 0, 0, 0, 0)
+            "60006000600060006000 73 0000000000000000000000000000000000002000 5a f1 50"
+            // DELEGATECALL(gas, 0x3000, 0, 0, 0, 0)
+            "6000600060006000 73 0000000000000000000000000000000000003000 5a f4 50"
+            // EXTCODESIZE(0x4000), 0x4000 is in access list
+            "73 0000000000000000000000000000000000004000 3b 50"
+            // SLOAD(2), slot is in access list
+            "600254 50"
+            // STATICCALL(gas, 0x04, 0, 0, 0, 0) to identity precompile
+            "6000600060006000 6004 5a fa 50 00"
+        ));
+
+        for record in [false, true] {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                contract,
+                AccountInfo::new(U256::ZERO, 1, Bytecode::new_raw(code.clone())),
+            );
+            let mut evm = test_evm(db, TransactTo::Call(contract), 1_000_000);
+            evm.env.cfg.record_cold_accesses = record;
+            evm.env.tx.access_list = vec![
+                (B160::from(0x4000), vec![]),
+                (contract, vec![U256::from(2)]),
+            ];
+            let ResultAndState {
+                result,
+                cold_accesses,
+                ..
+            } = evm.transact().unwrap();
+            assert!(result.is_success(), "{result:?}");
+
+            if !record {
+                assert!(cold_accesses.is_empty());
+                continue;
+            }
+            assert_eq!(
+                cold_accesses,
+                vec![
+                    ColdAccess {
+                        address: contract,
+                        slot: Some(U256::from(1)),
+                        opcode: opcode::SLOAD,
+                        gas_charged: 2100,
+                    },
+                    ColdAccess {
+                        address: B160::from(0x2000),
+                        slot: None,
+                        opcode: opcode::EXTCODESIZE,
+                        gas_charged: 2600,
+                    },
+                    ColdAccess {
+                        address: B160::from(0x3000),
+                        slot: None,
+                        opcode: opcode::DELEGATECALL,
+                        gas_charged: 2600,
+                    },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn gas_schedule() {
+        use crate::interpreter::opcode;
+
+        let contract = B160::from(0x2000);
+        let run = |gas_schedule: Option<GasSchedule>| {
+            let mut db = InMemoryDB::default();
+            // PUSH1 0 SLOAD STOP
+            db.insert_account_info(
+                contract,
+                AccountInfo::new(
+                    U256::ZERO,
+                    1,
+                    Bytecode::new_raw(Bytes::from_static(&hex!("600054 00"))),
+                ),
+            );
+            let mut evm = test_evm(db, TransactTo::Call(contract), 1_000_000);
+            evm.env.cfg.gas_schedule = gas_schedule;
+            evm.env.cfg.record_cold_accesses = true;
+            evm.env.tx.data = Bytes::from_static(&[1, 0, 1]);
+            let ResultAndState {
+                result,
+                cold_accesses,
+                ..
+            } = evm.transact().unwrap();
+            (result.gas_used(), cold_accesses)
+        };
+        let cold_sload = |gas_charged| {
+            vec![ColdAccess {
+                address: contract,
+                slot: Some(U256::ZERO),
+                opcode: opcode::SLOAD,
+                gas_charged,
+            }]
+        };
+
+        // 21000 base, 16 + 4 + 16 calldata, 3 PUSH1 and 2100 cold SLOAD.
+        assert_eq!(run(None), (23_139, cold_sload(2100)));
+        assert_eq!(
+            run(Some(GasSchedule::new(SpecId::LATEST))),
+            (23_139, cold_sload(2100))
+        );
+
+        // recorded cold access reports the cost charged by the schedule.
+        let schedule = GasSchedule {
+            calldata_zero_byte: 2,
+            calldata_nonzero_byte: 8,
+            cold_sload_cost: 800,
+            ..GasSchedule::new(SpecId::LATEST)
+        };
+        assert_eq!(
+            run(Some(schedule)),
+            (21_000 + 18 + 3 + 800, cold_sload(800))
+        );
+    }
+
+    #[test]
+    fn zero_refund_quotient() {
+        // PUSH1 0, PUSH1 0, SSTORE: clears slot 0.
+        let contract = B160::from(0x2000);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(
+                U256::ZERO,
+                1,
+                Bytecode::new_raw(Bytes::from_static(&hex!("6000600055"))),
+            ),
+        );
+        db.insert_account_storage(contract, U256::ZERO, U256::from(1))
+            .unwrap();
+        let mut evm = test_evm(db, TransactTo::Call(contract), 1_000_000);
+        assert!(gas_refunded(evm.clone()) > 0);
+
+        evm.env.cfg.gas_schedule = Some(GasSchedule {
+            max_refund_quotient: 0,
+            ..GasSchedule::new(SpecId::LATEST)
+        });
+        assert_eq!(gas_refunded(evm), 0);
+    }
+
+    #[test]
+    fn system_call() {
+        use crate::db::DatabaseCommit;
+        use crate::primitives::{SYSTEM_ADDRESS, SYSTEM_CALL_GAS_LIMIT};
+
+        // Ring buffer like the beacon roots contract, stores the first calldata word at
+        // `timestamp % 8191` and the caller at slot 8191.
+        // PUSH1 0 CALLDATALOAD PUSH2 0x1fff TIMESTAMP MOD SSTORE CALLER PUSH2 0x1fff SSTORE STOP
+        let contract = B160::from(0x4788);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(
+                U256::ZERO,
+                1,
+                Bytecode::new_raw(Bytes::from_static(&hex!(
+                    "600035 611fff 42 06 55 33 611fff 55 00"
+                ))),
+            ),
+        );
+
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.cfg.spec_id = SpecId::CANCUN;
+        evm.env.block.timestamp = U256::from(8191 + 12);
+        // would reject a transaction with zero gas price and 30M gas limit.
+        evm.env.block.basefee = U256::from(7);
+        evm.env.block.gas_limit = U256::from(1_000_000);
+        evm.env.tx.caller = B160::from(0x1000);
+
+        let root = U256::from(0xbeac);
+        let ResultAndState { result, state, .. } = evm
+            .transact_system_call(contract, Bytes::from(root.to_be_bytes_vec()))
+            .unwrap();
+        assert!(result.is_success());
+        assert!(result.gas_used() < SYSTEM_CALL_GAS_LIMIT);
+        assert!(!state.contains_key(&SYSTEM_ADDRESS));
+        assert!(!state.contains_key(&evm.env.block.coinbase));
+        assert_eq!(evm.env.tx.caller, B160::from(0x1000));
+
+        evm.db().unwrap().commit(state);
+        let db = evm.db().unwrap();
+        let storage = &db.accounts[&contract].storage;
+        assert_eq!(storage[&U256::from(12)], root);
+        let caller = storage[&U256::from(8191)].to_be_bytes::<32>();
+        assert_eq!(B160::from_slice(&caller[12..]), SYSTEM_ADDRESS);
+    }
+
+    #[test]
+    fn preverify_transaction() {
+        let caller = B160::from(0x1000);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(100_000)));
+
+        // only the balance check fails.
+        let mut evm = test_evm(db, TransactTo::Call(B160::from(0x2000)), 100_001);
+        evm.env.tx.caller = caller;
+        evm.env.tx.gas_price = U256::from(1);
+        assert!(matches!(
+            evm.preverify_transaction(),
+            Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForMaxFee { .. }
+            ))
+        ));
+
+        evm.env.tx.gas_limit = 100_000;
+        let verified = evm.preverify_transaction().unwrap();
+        assert_eq!(verified.initial_gas(), 21_000);
+        assert_eq!(verified.caller_nonce(), 0);
+
+        // stale environment.
+        evm.env.tx.gas_price = U256::from(2);
+        assert!(matches!(
+            evm.transact_preverified(verified.clone()),
+            Err(EVMError::VerifiedTxMismatch)
+        ));
+        evm.env.tx.gas_price = U256::from(1);
+
+        let result = evm.transact_preverified(verified.clone()).unwrap().result;
+        assert_eq!(result.gas_used(), 21_000);
+        assert_eq!(result, evm.transact().unwrap().result);
+
+        // stale caller nonce.
+        evm.transact_commit().unwrap();
+        assert!(matches!(
+            evm.transact_preverified(verified),
+            Err(EVMError::VerifiedTxMismatch)
+        ));
+
+        let fund = |evm: &mut EVM<InMemoryDB>, balance: u64| {
+            evm.db().unwrap().insert_account_info(
+                caller,
+                AccountInfo {
+                    nonce: 1,
+                    ..AccountInfo::from_balance(U256::from(balance))
+                },
+            )
+        };
+        fund(&mut evm, 100_000);
+
+        // intrinsic gas depends on the storage keys, not only on the access list length.
+        let target = B160::from(0x2000);
+        evm.env.tx.access_list = vec![(target, vec![])];
+        let verified = evm.preverify_transaction().unwrap();
+        evm.env.tx.access_list = vec![(target, vec![U256::from(1)])];
+        assert!(matches!(
+            evm.transact_preverified(verified.clone()),
+            Err(EVMError::VerifiedTxMismatch)
+        ));
+        evm.env.tx.access_list = vec![(target, vec![])];
+
+        let stale: [fn(&mut EVM<InMemoryDB>); 4] = [
+            |evm| evm.env.cfg.spec_id = SpecId::MERGE,
+            |evm| evm.env.tx.transact_to = TransactTo::Call(B160::from(0x3000)),
+            |evm| evm.env.tx.chain_id = Some(1),
+            |evm| evm.env.cfg.gas_schedule = Some(GasSchedule::new(SpecId::LATEST)),
+        ];
+        for modify in stale {
+            let mut evm = evm.clone();
+            modify(&mut evm);
+            assert!(matches!(
+                evm.transact_preverified(verified.clone()),
+                Err(EVMError::VerifiedTxMismatch)
+            ));
+        }
+
+        // balance dropped without a nonce change.
+        fund(&mut evm, 1_000);
+        assert!(matches!(
+            evm.transact_preverified(verified),
+            Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForMaxFee { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn allowed_sender_code_prefixes() {
+        use crate::db::{CacheDB, WrapDatabaseSplitCode};
+
+        let code = |hex: &'static [u8]| Bytecode::new_raw(Bytes::from_static(hex));
+        let (plain, contract, delegated) = (B160::from(1), B160::from(2), B160::from(3));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(plain, AccountInfo::from_balance(U256::from(1)));
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(U256::ZERO, 0, code(&hex!("6000"))),
+        );
+        // EIP-7702 delegation designator.
+        let delegation = code(&hex!("ef01000000000000000000000000000000000000001000"));
+        let delegation_hash = delegation.hash();
+        db.insert_account_info(delegated, AccountInfo::new(U256::ZERO, 0, delegation));
+
+        // code is not returned with the account, it needs to be read separately.
+        let mut evm = crate::new();
+        evm.database(CacheDB::new(WrapDatabaseSplitCode::new(db)));
+        evm.env.tx.transact_to = TransactTo::Call(B160::from(0x1000));
+        evm.env.tx.gas_limit = 21_000;
+        let mut transact_from = |caller, prefixes: &[&'static [u8]]| {
+            evm.env.tx.caller = caller;
+            evm.env.cfg.allowed_sender_code_prefixes =
+                prefixes.iter().copied().map(Bytes::from_static).collect();
+            evm.transact().map(|_| ())
+        };
+        let rejected = Err(EVMError::Transaction(
+            InvalidTransaction::RejectCallerWithCode,
+        ));
+
+        assert_eq!(transact_from(delegated, &[]), rejected);
+        assert_eq!(transact_from(delegated, &[&hex!("ef0100")]), Ok(()));
+        assert_eq!(transact_from(contract, &[&hex!("ef0100")]), rejected);
+        assert_eq!(transact_from(plain, &[]), Ok(()));
+        assert_eq!(transact_from(plain, &[&hex!("ef0100")]), Ok(()));
+
+        // only the prefix was read, the whole code was not loaded into the cache.
+        assert!(!evm.db.unwrap().contracts.contains_key(&delegation_hash));
+    }
 }
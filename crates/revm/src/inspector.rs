@@ -1,5 +1,6 @@
 use crate::evm_impl::EVMData;
 use crate::interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter};
+use crate::precompile::PrecompileResult;
 use crate::primitives::{db::Database, Bytes, B160, B256};
 
 use auto_impl::auto_impl;
@@ -7,6 +8,7 @@ use auto_impl::auto_impl;
 #[cfg(feature = "std")]
 pub mod customprinter;
 pub mod gas;
+pub mod jump_trace;
 pub mod noop;
 #[cfg(all(feature = "std", feature = "serde"))]
 pub mod tracer_eip3155;
@@ -16,6 +18,7 @@ pub mod inspectors {
     #[cfg(feature = "std")]
     pub use super::customprinter::CustomPrintTracer;
     pub use super::gas::GasInspector;
+    pub use super::jump_trace::JumpTraceInspector;
     pub use super::noop::NoOpInspector;
     #[cfg(all(feature = "std", feature = "serde"))]
     pub use super::tracer_eip3155::TracerEip3155;
@@ -99,6 +102,19 @@ pub trait Inspector<DB: Database> {
         (ret, remaining_gas, out)
     }
 
+    /// Called when a precompile is about to be executed.
+    ///
+    /// Precompile calls go through [Inspector::call] and [Inspector::call_end] like any other
+    /// call, this hook is called in between them, right before the precompile function is run.
+    fn precompile_call(&mut self, _address: B160, _input: &Bytes, _gas_limit: u64) {}
+
+    /// Called when a precompile has been executed.
+    ///
+    /// `gas_used` is the gas charged to the call. On error, or if the precompile needs more than
+    /// the gas limit, it is the whole gas limit.
+    fn precompile_call_end(&mut self, _address: B160, _outcome: &PrecompileResult, _gas_used: u64) {
+    }
+
     /// Called when a contract is about to be created.
     ///
     /// InstructionResulting anything other than [InstructionResult::Continue] overrides the result of the creation.
@@ -134,3 +150,124 @@ pub trait Inspector<DB: Database> {
     /// Called when a contract has been self-destructed with funds transferred to target.
     fn selfdestruct(&mut self, _contract: B160, _target: B160) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryDB;
+    use crate::interpreter::opcode;
+    use crate::precompile::PrecompileError;
+    use crate::primitives::{hex_literal::hex, AccountInfo, Bytecode, State, TransactTo, U256};
+
+    #[test]
+    fn precompile_hooks() {
+        /// Records precompile calls as `(address, gas_limit, outcome, gas_used)`.
+        #[derive(Default)]
+        struct PrecompileRecorder {
+            calls: Vec<(B160, u64)>,
+            ends: Vec<(B160, PrecompileResult, u64)>,
+        }
+
+        impl<DB: Database> Inspector<DB> for PrecompileRecorder {
+            fn precompile_call(&mut self, address: B160, _input: &Bytes, gas_limit: u64) {
+                self.calls.push((address, gas_limit));
+            }
+
+            fn precompile_call_end(
+                &mut self,
+                address: B160,
+                outcome: &PrecompileResult,
+                gas_used: u64,
+            ) {
+                self.ends.push((address, outcome.clone(), gas_used));
+            }
+        }
+
+        let ecrecover = B160::from_low_u64_be(1);
+        let call = |gas_limit: u64, recorder: &mut PrecompileRecorder| {
+            let mut evm = crate::new();
+            evm.database(InMemoryDB::default());
+            evm.env.tx.transact_to = TransactTo::Call(ecrecover);
+            evm.env.tx.data = Bytes::from_static(&[0u8; 128]);
+            evm.env.tx.gas_limit = gas_limit;
+            evm.inspect(recorder).unwrap().result
+        };
+
+        // an invalid signature still costs the base fee and returns nothing.
+        let mut recorder = PrecompileRecorder::default();
+        let result = call(100_000, &mut recorder);
+        assert!(result.is_success());
+        let intrinsic = 21_000 + 128 * 4;
+        assert_eq!(recorder.calls, vec![(ecrecover, 100_000 - intrinsic)]);
+        assert_eq!(
+            recorder.ends,
+            vec![(ecrecover, Ok((3000, Vec::new())), 3000)]
+        );
+
+        // out of gas consumes the whole limit.
+        let mut recorder = PrecompileRecorder::default();
+        call(intrinsic + 2_000, &mut recorder);
+        assert_eq!(
+            recorder.ends,
+            vec![(ecrecover, Err(PrecompileError::OutOfGas), 2_000)]
+        );
+    }
+
+    #[test]
+    fn patch_sload_result() {
+        /// Replaces the value loaded by SLOAD once it is on the stack.
+        struct SloadPatch {
+            value: U256,
+            in_sload: bool,
+        }
+
+        impl<DB: Database> Inspector<DB> for SloadPatch {
+            fn step(
+                &mut self,
+                interp: &mut Interpreter,
+                _data: &mut EVMData<'_, DB>,
+            ) -> InstructionResult {
+                self.in_sload = interp.current_opcode() == opcode::SLOAD;
+                InstructionResult::Continue
+            }
+
+            fn step_end(
+                &mut self,
+                interp: &mut Interpreter,
+                _data: &mut EVMData<'_, DB>,
+                eval: InstructionResult,
+            ) -> InstructionResult {
+                if self.in_sload {
+                    interp.stack.set(0, self.value).unwrap();
+                }
+                eval
+            }
+        }
+
+        // PUSH1 0, SLOAD, PUSH1 1, SSTORE: copies slot 0 to slot 1.
+        let contract = B160::from(0x1000);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(
+                U256::ZERO,
+                1,
+                Bytecode::new_raw(Bytes::from_static(&hex!("6000546001 55"))),
+            ),
+        );
+        db.insert_account_storage(contract, U256::ZERO, U256::from(7))
+            .unwrap();
+        let mut evm = crate::new();
+        evm.database(db);
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        evm.env.tx.gas_limit = 100_000;
+
+        let slot = |state: &State| state[&contract].storage[&U256::from(1)].present_value;
+        assert_eq!(slot(&evm.transact().unwrap().state), U256::from(7));
+        let patch = SloadPatch {
+            value: U256::from(99),
+            in_sload: false,
+        };
+        assert_eq!(slot(&evm.inspect(patch).unwrap().state), U256::from(99));
+    }
+}
@@ -0,0 +1,135 @@
+//! JumpTraceInspector. Records control flow edges of `JUMP` and `JUMPI`.
+
+use crate::interpreter::{opcode, InstructionResult, Interpreter};
+use crate::primitives::{db::Database, HashMap, B256, U256};
+use crate::{evm_impl::EVMData, Inspector};
+use alloc::vec::Vec;
+
+/// Single executed jump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JumpEdge {
+    /// Program counter of the jump instruction.
+    pub from: usize,
+    /// Program counter of the next executed instruction.
+    ///
+    /// For `JUMPI` that is not taken this is `from + 1`.
+    pub to: usize,
+    /// True for `JUMPI`.
+    pub conditional: bool,
+    /// False only for `JUMPI` with zero condition.
+    pub taken: bool,
+}
+
+/// Inspector that collects executed `JUMP` and `JUMPI` edges, grouped by the code hash of the
+/// executed bytecode, in execution order.
+///
+/// Only the opcode is checked on every step, the stack is read only for jumps. Jumps that fail
+/// with invalid destination are not recorded.
+#[derive(Clone, Debug, Default)]
+pub struct JumpTraceInspector {
+    edges: HashMap<B256, Vec<JumpEdge>>,
+    /// Jump that is being executed, `to` is set once it is executed.
+    pending: Option<JumpEdge>,
+}
+
+impl JumpTraceInspector {
+    /// Returns collected edges per code hash.
+    pub fn edges(&self) -> &HashMap<B256, Vec<JumpEdge>> {
+        &self.edges
+    }
+
+    /// Consumes the inspector and returns collected edges per code hash.
+    pub fn into_edges(self) -> HashMap<B256, Vec<JumpEdge>> {
+        self.edges
+    }
+}
+
+impl<DB: Database> Inspector<DB> for JumpTraceInspector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EVMData<'_, DB>) -> InstructionResult {
+        let opcode = interp.current_opcode();
+        if opcode == opcode::JUMP || opcode == opcode::JUMPI {
+            let conditional = opcode == opcode::JUMPI;
+            // condition of `JUMPI` is the second stack item. If the stack underflows the
+            // instruction fails and nothing is recorded.
+            let taken = !conditional || interp.stack.peek(1).map_or(false, |v| v != U256::ZERO);
+            self.pending = Some(JumpEdge {
+                from: interp.program_counter(),
+                to: 0,
+                conditional,
+                taken,
+            });
+        }
+        InstructionResult::Continue
+    }
+
+    #[inline]
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DB>,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        if let Some(mut edge) = self.pending.take() {
+            if eval == InstructionResult::Continue {
+                edge.to = interp.program_counter();
+                self.edges
+                    .entry(interp.contract.bytecode.hash())
+                    .or_default()
+                    .push(edge);
+            }
+        }
+        InstructionResult::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JumpEdge, JumpTraceInspector};
+    use crate::db::BenchmarkDB;
+    use crate::primitives::{hex_literal::hex, Bytecode, Bytes, TransactTo, B160};
+
+    #[test]
+    fn jump_edges() {
+        // 0: PUSH1 1, 2: PUSH1 8, 4: JUMPI (taken), 5: INVALID x3, 8: JUMPDEST, 9: PUSH1 0,
+        // 11: PUSH1 8, 13: JUMPI (not taken), 14: PUSH1 18, 16: JUMP, 17: INVALID, 18: JUMPDEST, 19: STOP
+        let code = Bytecode::new_raw(Bytes::from_static(&hex!(
+            "6001 6008 57 fe fe fe 5b 6000 6008 57 6012 56 fe 5b 00"
+        )));
+        let code_hash = code.hash();
+
+        let mut evm = crate::new();
+        evm.database(BenchmarkDB::new_bytecode(code));
+        evm.env.tx.caller = B160::from(1);
+        evm.env.tx.transact_to = TransactTo::Call(B160::zero());
+        evm.env.tx.gas_limit = 100_000;
+
+        let mut inspector = JumpTraceInspector::default();
+        evm.inspect(&mut inspector).unwrap();
+
+        assert_eq!(
+            inspector.edges()[&code_hash],
+            vec![
+                JumpEdge {
+                    from: 4,
+                    to: 8,
+                    conditional: true,
+                    taken: true,
+                },
+                JumpEdge {
+                    from: 13,
+                    to: 14,
+                    conditional: true,
+                    taken: false,
+                },
+                JumpEdge {
+                    from: 16,
+                    to: 18,
+                    conditional: false,
+                    taken: true,
+                },
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::inspectors::GasInspector;
 use crate::interpreter::{CallInputs, CreateInputs, Gas, InstructionResult};
+use crate::precompile::PrecompileResult;
 use crate::primitives::{db::Database, hex, Bytes, B160};
 use crate::{evm_impl::EVMData, Inspector};
 use revm_interpreter::primitives::U256;
@@ -121,6 +122,18 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
         (ret, remaining_gas, out)
     }
 
+    fn precompile_call_end(&mut self, address: B160, outcome: &PrecompileResult, gas_used: u64) {
+        // precompiles have no steps, mark their frame with a single line.
+        let log_line = json!({
+            "precompile": format!("0x{}", hex::encode(address)),
+            "gasUsed": format!("0x{gas_used:x}"),
+            "error": outcome.as_ref().err().map(|e| format!("{e:?}")),
+        });
+
+        writeln!(self.output, "{}", serde_json::to_string(&log_line).unwrap())
+            .expect("If output fails we can ignore the logging");
+    }
+
     fn create(
         &mut self,
         data: &mut EVMData<'_, DB>,
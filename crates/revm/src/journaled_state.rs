@@ -1,13 +1,54 @@
 use crate::interpreter::{inner_models::SelfDestructResult, InstructionResult};
 use crate::primitives::{
-    db::Database, hash_map::Entry, Account, Bytecode, HashMap, Log, State, StorageSlot, B160,
-    KECCAK_EMPTY, U256,
+    db::Database, hash_map::Entry, Account, Bytecode, ColdAccess, CreatedContract, HashMap,
+    HashSet, Log, SelfdestructEvent, State, StorageSlot, B160, B256, KECCAK_EMPTY, U256,
 };
 use alloc::{vec, vec::Vec};
 use core::mem::{self};
 use revm_interpreter::primitives::Spec;
-use revm_interpreter::primitives::SpecId::SPURIOUS_DRAGON;
-
+use revm_interpreter::primitives::SpecId::{self, SPURIOUS_DRAGON};
+
+/// State of accounts loaded in a transaction with a journal of changes that can be reverted.
+///
+/// Every [`Self::checkpoint`] starts a new set of journal entries, [`Self::checkpoint_revert`]
+/// reverts all entries from the checkpoint onward and [`Self::checkpoint_commit`] keeps them so
+/// they are reverted together with the parent checkpoint.
+///
+/// Accounts and storage slots loaded inside of a checkpoint are journaled as well, so after
+/// a revert they are removed from the state and are cold again, as EIP-2929 requires. Accounts
+/// and slots loaded before the checkpoint or with [`Self::initial_account_load`] stay warm.
+///
+/// Manual transfer that is reverted:
+///
+/// ```
+/// use revm::{
+///     db::InMemoryDB,
+///     primitives::{AccountInfo, SpecId, B160, U256},
+///     JournaledState,
+/// };
+///
+/// let (alice, bob) = (B160::from(0x1000), B160::from(0x2000));
+/// let mut db = InMemoryDB::default();
+/// db.insert_account_info(
+///     alice,
+///     AccountInfo {
+///         balance: U256::from(100),
+///         ..Default::default()
+///     },
+/// );
+///
+/// let mut journal = JournaledState::new_with_spec(SpecId::LATEST, 0);
+/// journal.initial_account_load(alice, &[], &mut db).unwrap();
+///
+/// let checkpoint = journal.checkpoint();
+/// journal.transfer(&alice, &bob, U256::from(40), &mut db).unwrap();
+/// assert_eq!(journal.account(bob).info.balance, U256::from(40));
+/// journal.checkpoint_revert(checkpoint);
+///
+/// // balance is returned and `bob`, loaded inside of the checkpoint, is cold again.
+/// assert_eq!(journal.account(alice).info.balance, U256::from(100));
+/// assert!(!journal.state.contains_key(&bob));
+/// ```
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct JournaledState {
@@ -15,10 +56,23 @@ pub struct JournaledState {
     pub state: State,
     /// logs
     pub logs: Vec<Log>,
+    /// Contracts created in this transaction. Reverted creations are removed on checkpoint revert.
+    pub created_contracts: Vec<CreatedContract>,
+    /// Selfdestructs done in this transaction. Reverted ones are removed on checkpoint revert.
+    pub selfdestructs: Vec<SelfdestructEvent>,
+    /// Recorded cold accesses. They are not removed on checkpoint revert as their gas was
+    /// charged.
+    pub cold_accesses: Vec<ColdAccess>,
     /// how deep are we in call stack.
     pub depth: usize,
     /// journal with changes that happened between calls.
     pub journal: Vec<Vec<JournalEntry>>,
+    /// Storage slots that already have a journal entry since the start of each open
+    /// checkpoint, the last set is of the current one.
+    ///
+    /// Reverting a checkpoint only needs the earliest value of a slot, so later changes of
+    /// these slots are not journaled. Sets of committed checkpoints are merged into the parent.
+    pub journaled_slots: Vec<HashSet<(B160, U256)>>,
     /// Ethereum before EIP-161 differently defined empty and not-existing account
     /// so we need to take care of that difference. Set this to false if you are handling
     /// legacy transactions
@@ -75,8 +129,11 @@ pub enum JournalEntry {
 }
 
 /// SubRoutine checkpoint that will help us to go back from this
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct JournalCheckpoint {
     log_i: usize,
+    created_i: usize,
+    selfdestruct_i: usize,
     journal_i: usize,
 }
 
@@ -92,7 +149,11 @@ impl JournaledState {
         Self {
             state: HashMap::new(),
             logs: Vec::new(),
+            created_contracts: Vec::new(),
+            selfdestructs: Vec::new(),
+            cold_accesses: Vec::new(),
             journal: vec![vec![]],
+            journaled_slots: vec![HashSet::new()],
             depth: 0,
             is_before_spurious_dragon: false,
             num_of_precompiles,
@@ -108,6 +169,17 @@ impl JournaledState {
         journal
     }
 
+    /// Creates new JournaledState for the given spec.
+    ///
+    /// Before Spurious Dragon this is same as [`Self::new_legacy`], after it same as [`Self::new`].
+    pub fn new_with_spec(spec_id: SpecId, num_of_precompiles: usize) -> JournaledState {
+        if SpecId::enabled(spec_id, SPURIOUS_DRAGON) {
+            Self::new(num_of_precompiles)
+        } else {
+            Self::new_legacy(num_of_precompiles)
+        }
+    }
+
     /// Return reference to state.
     pub fn state(&mut self) -> &mut State {
         &mut self.state
@@ -116,6 +188,9 @@ impl JournaledState {
     /// Mark account as touched as only touched accounts will be added to state.
     /// This is expecially important for state clear where touched empty accounts needs to
     /// be removed from state.
+    ///
+    /// Account needs to be loaded, otherwise this does nothing. Touch is reverted on checkpoint
+    /// revert.
     pub fn touch(&mut self, address: &B160) {
         if let Some(account) = self.state.get_mut(address) {
             Self::touch_account(self.journal.last_mut().unwrap(), address, account);
@@ -134,11 +209,39 @@ impl JournaledState {
         let state = mem::take(&mut self.state);
 
         let logs = mem::take(&mut self.logs);
+        self.created_contracts.clear();
+        self.selfdestructs.clear();
+        self.cold_accesses.clear();
         self.journal = vec![vec![]];
+        self.journaled_slots = vec![HashSet::new()];
         self.depth = 0;
         (state, logs)
     }
 
+    /// Take contracts created since last finalize, with `destroyed` flag set
+    /// from the current state.
+    pub fn take_created_contracts(&mut self) -> Vec<CreatedContract> {
+        let mut created = mem::take(&mut self.created_contracts);
+        for contract in created.iter_mut() {
+            contract.destroyed = self
+                .state
+                .get(&contract.address)
+                .map(|account| account.is_selfdestructed())
+                .unwrap_or_default();
+        }
+        created
+    }
+
+    /// Take selfdestructs done since last finalize.
+    pub fn take_selfdestructs(&mut self) -> Vec<SelfdestructEvent> {
+        mem::take(&mut self.selfdestructs)
+    }
+
+    /// Take cold accesses recorded since last finalize.
+    pub fn take_cold_accesses(&mut self) -> Vec<ColdAccess> {
+        mem::take(&mut self.cold_accesses)
+    }
+
     /// Use it with load_account function.
     pub fn account(&self, address: B160) -> &Account {
         self.state.get(&address).unwrap() // Always assume that acc is already loaded
@@ -402,20 +505,38 @@ impl JournaledState {
         }
     }
 
+    /// Creates a checkpoint and enters a new call depth.
+    ///
+    /// Every checkpoint has to be either committed or reverted, checkpoints are nested so the
+    /// last created one has to be finished first.
     pub fn checkpoint(&mut self) -> JournalCheckpoint {
         let checkpoint = JournalCheckpoint {
             log_i: self.logs.len(),
+            created_i: self.created_contracts.len(),
+            selfdestruct_i: self.selfdestructs.len(),
             journal_i: self.journal.len(),
         };
         self.depth += 1;
         self.journal.push(Default::default());
+        self.journaled_slots.push(HashSet::new());
         checkpoint
     }
 
+    /// Commits the last checkpoint and returns to the parent call depth.
+    ///
+    /// Changes are kept in the journal and are reverted if the parent checkpoint is reverted.
     pub fn checkpoint_commit(&mut self) {
         self.depth -= 1;
+        let slots = self.journaled_slots.pop().unwrap();
+        self.journaled_slots.last_mut().unwrap().extend(slots);
     }
 
+    /// Reverts all changes since the checkpoint and returns to the parent call depth.
+    ///
+    /// Balances, nonces, code, storage values, touch and selfdestruct flags are restored.
+    /// Logs, created contracts and selfdestruct events of the checkpoint are removed. Accounts
+    /// and storage slots loaded since the checkpoint are removed from the state, so they are
+    /// cold if they are loaded again. Recorded cold accesses are kept as their gas was charged.
     pub fn checkpoint_revert(&mut self, checkpoint: JournalCheckpoint) {
         let is_spurious_dragon_enabled = !self.is_before_spurious_dragon;
         let state = &mut self.state;
@@ -429,10 +550,16 @@ impl JournaledState {
             .for_each(|cs| Self::journal_revert(state, mem::take(cs), is_spurious_dragon_enabled));
 
         self.logs.truncate(checkpoint.log_i);
+        self.created_contracts.truncate(checkpoint.created_i);
+        self.selfdestructs.truncate(checkpoint.selfdestruct_i);
         self.journal.truncate(checkpoint.journal_i);
+        self.journaled_slots.pop();
     }
 
     /// transfer balance from address to target. Check if target exist/is_cold
+    ///
+    /// Account at `address` needs to be loaded. Selfdestruct flag and the balance transfer are
+    /// reverted on checkpoint revert.
     pub fn selfdestruct<DB: Database>(
         &mut self,
         address: B160,
@@ -465,6 +592,11 @@ impl JournaledState {
                 was_destroyed: previously_destroyed,
                 had_balance: balance,
             });
+        self.selfdestructs.push(SelfdestructEvent {
+            contract: address,
+            beneficiary: target,
+            value: balance,
+        });
 
         Ok(SelfDestructResult {
             had_value: balance != U256::ZERO,
@@ -481,7 +613,7 @@ impl JournaledState {
     ) -> Result<&mut Account, DB::Error> {
         let account = self.initial_account_load(address, &[], db)?;
         if account.info.code.is_none() {
-            if account.info.code_hash == KECCAK_EMPTY {
+            if is_empty_code_hash(account.info.code_hash) {
                 account.info.code = Some(Bytecode::new());
             } else {
                 // load code if requested
@@ -492,7 +624,8 @@ impl JournaledState {
         Ok(account)
     }
 
-    /// Initial load of account. This load will not be tracked inside journal
+    /// Initial load of account. This load will not be tracked inside journal, account stays
+    /// warm even if checkpoints are reverted.
     pub fn initial_account_load<DB: Database>(
         &mut self,
         address: B160,
@@ -502,6 +635,13 @@ impl JournaledState {
         match self.state.entry(address) {
             Entry::Occupied(entry) => {
                 let account = entry.into_mut();
+                // account can be loaded already, for example as the coinbase, slots still
+                // need to be loaded.
+                for slot in slots {
+                    if let Entry::Vacant(vac) = account.storage.entry(*slot) {
+                        vac.insert(StorageSlot::new(db.storage(address, *slot)?));
+                    }
+                }
 
                 Ok(account)
             }
@@ -522,6 +662,9 @@ impl JournaledState {
     }
 
     /// load account into memory. return if it is cold or hot accessed
+    ///
+    /// Account is cold if it was not loaded before, precompiles are always warm. Load is
+    /// journaled and reverted on checkpoint revert.
     pub fn load_account<DB: Database>(
         &mut self,
         address: B160,
@@ -550,7 +693,10 @@ impl JournaledState {
         })
     }
 
-    // first is is_cold second bool is exists.
+    /// Loads account and returns if it is cold and if it exists.
+    ///
+    /// Before EIP-161 account exists if it was in the database or was touched in this
+    /// transaction, even if it is empty. After EIP-161 account exists if it is not empty.
     pub fn load_account_exist<DB: Database>(
         &mut self,
         address: B160,
@@ -576,7 +722,7 @@ impl JournaledState {
     ) -> Result<(&mut Account, bool), DB::Error> {
         let (acc, is_cold) = self.load_account(address, db)?;
         if acc.info.code.is_none() {
-            if acc.info.code_hash == KECCAK_EMPTY {
+            if is_empty_code_hash(acc.info.code_hash) {
                 let empty = Bytecode::new();
                 acc.info.code = Some(empty);
             } else {
@@ -587,7 +733,10 @@ impl JournaledState {
         Ok((acc, is_cold))
     }
 
-    // account is already present and loaded.
+    /// Loads storage slot and returns its present value and if it is cold.
+    ///
+    /// Account needs to be loaded, this panics otherwise. Load is journaled and reverted on
+    /// checkpoint revert.
     pub fn sload<DB: Database>(
         &mut self,
         address: B160,
@@ -614,6 +763,10 @@ impl JournaledState {
                         key,
                         had_value: None,
                     });
+                self.journaled_slots
+                    .last_mut()
+                    .unwrap()
+                    .insert((address, key));
 
                 vac.insert(StorageSlot::new(value));
 
@@ -624,7 +777,10 @@ impl JournaledState {
     }
 
     /// account should already be present in our state.
-    /// returns (original,present,new) slot
+    /// returns (original,present,new) slot and if it is cold.
+    ///
+    /// New value is reverted on checkpoint revert. Only the first change of the slot since the
+    /// checkpoint is journaled, see [`Self::journaled_slots`].
     pub fn sstore<DB: Database>(
         &mut self,
         address: B160,
@@ -644,14 +800,22 @@ impl JournaledState {
             return Ok((slot.original_value, present, new, is_cold));
         }
 
-        self.journal
+        // revert of the checkpoint restores the earliest journaled value of the slot.
+        if self
+            .journaled_slots
             .last_mut()
             .unwrap()
-            .push(JournalEntry::StorageChange {
-                address,
-                key,
-                had_value: Some(present),
-            });
+            .insert((address, key))
+        {
+            self.journal
+                .last_mut()
+                .unwrap()
+                .push(JournalEntry::StorageChange {
+                    address,
+                    key,
+                    had_value: Some(present),
+                });
+        }
         // insert value into present state.
         slot.present_value = new;
         Ok((slot.original_value, present, new, is_cold))
@@ -674,9 +838,243 @@ pub fn is_precompile(address: B160, num_of_precompiles: usize) -> bool {
     num.wrapping_sub(1) < num_of_precompiles as u16
 }
 
+/// Databases that do not carry code in [AccountInfo](crate::primitives::AccountInfo) can
+/// return zero hash for accounts without code, there is no bytecode to fetch for them.
+#[inline(always)]
+fn is_empty_code_hash(code_hash: B256) -> bool {
+    code_hash == KECCAK_EMPTY || code_hash == B256::zero()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::db::InMemoryDB;
+    use crate::primitives::AccountInfo;
+
+    fn db_with_balance(address: B160, balance: u64) -> InMemoryDB {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            address,
+            AccountInfo {
+                balance: U256::from(balance),
+                ..Default::default()
+            },
+        );
+        db
+    }
+
+    #[test]
+    fn warm_set_is_reverted() {
+        let (a, b) = (B160::from(0x1000), B160::from(0x2000));
+        let mut db = db_with_balance(a, 100);
+        let mut journal = JournaledState::new_with_spec(SpecId::LATEST, 0);
+
+        journal.initial_account_load(a, &[], &mut db).unwrap();
+        assert!(!journal.load_account(a, &mut db).unwrap().1);
+
+        let checkpoint = journal.checkpoint();
+        assert!(journal.load_account(b, &mut db).unwrap().1);
+        assert!(journal.sload(a, U256::from(1), &mut db).unwrap().1);
+        assert!(!journal.load_account(b, &mut db).unwrap().1);
+        journal.checkpoint_revert(checkpoint);
+
+        // loads made in the checkpoint are cold again, initial load stays warm.
+        assert!(!journal.load_account(a, &mut db).unwrap().1);
+        assert!(journal.load_account(b, &mut db).unwrap().1);
+        assert!(journal.sload(a, U256::from(1), &mut db).unwrap().1);
+
+        journal.checkpoint();
+        assert!(journal.sload(a, U256::from(2), &mut db).unwrap().1);
+        journal.checkpoint_commit();
+        assert!(!journal.sload(a, U256::from(2), &mut db).unwrap().1);
+        assert_eq!(journal.depth(), 0);
+    }
+
+    #[test]
+    fn storage_changes_are_journaled_once() {
+        let a = B160::from(0x1000);
+        let mut db = db_with_balance(a, 0);
+        db.insert_account_storage(a, U256::from(1), U256::from(7))
+            .unwrap();
+        let mut journal = JournaledState::new_with_spec(SpecId::LATEST, 0);
+        journal.initial_account_load(a, &[], &mut db).unwrap();
+        let key = U256::from(1);
+        let present = |journal: &JournaledState| journal.account(a).storage[&key].present_value;
+
+        let outer = journal.checkpoint();
+        for value in 0..1000 {
+            journal.sstore(a, key, U256::from(value), &mut db).unwrap();
+        }
+        // cold load is the only entry, its revert removes the slot.
+        assert_eq!(journal.journal.last().unwrap().len(), 1);
+
+        // first change in the inner checkpoint is journaled again.
+        let inner = journal.checkpoint();
+        journal.sstore(a, key, U256::from(1), &mut db).unwrap();
+        journal.sstore(a, key, U256::from(2), &mut db).unwrap();
+        assert_eq!(journal.journal.last().unwrap().len(), 1);
+        journal.checkpoint_revert(inner);
+        assert_eq!(present(&journal), U256::from(999));
+
+        journal.checkpoint();
+        journal.sstore(a, key, U256::from(3), &mut db).unwrap();
+        journal.checkpoint_commit();
+        // slot is already journaled by the committed checkpoint.
+        journal.sstore(a, key, U256::from(4), &mut db).unwrap();
+        assert_eq!(journal.journal.last().unwrap().len(), 1);
+
+        journal.checkpoint_revert(outer);
+        assert!(!journal.account(a).storage.contains_key(&key));
+        assert_eq!(
+            journal.sload(a, key, &mut db).unwrap(),
+            (U256::from(7), true)
+        );
+    }
+
+    #[test]
+    fn storage_journal_matches_model() {
+        let a = B160::from(0x1000);
+        let mut db = db_with_balance(a, 0);
+        for key in 0..4 {
+            db.insert_account_storage(a, U256::from(key), U256::from(key + 10))
+                .unwrap();
+        }
+
+        // xorshift, seeded to keep the test deterministic.
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |n: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % n
+        };
+
+        // every change is journaled when the sets of journaled slots are empty.
+        let sstore_uncompacted = |journal: &mut JournaledState, key, value, db: &mut InMemoryDB| {
+            journal.journaled_slots.iter_mut().for_each(HashSet::clear);
+            journal.sstore(a, key, value, db).unwrap()
+        };
+
+        for _ in 0..200 {
+            let mut journal = JournaledState::new_with_spec(SpecId::LATEST, 0);
+            journal.initial_account_load(a, &[], &mut db).unwrap();
+            let mut uncompacted = journal.clone();
+            // model of slot values and warm slots, with a snapshot per open checkpoint.
+            let mut values: HashMap<U256, U256> = (0..4)
+                .map(|key| (U256::from(key), U256::from(key + 10)))
+                .collect();
+            let mut warm: HashSet<U256> = HashSet::new();
+            let mut checkpoints = Vec::new();
+
+            for _ in 0..64 {
+                match next(6) {
+                    0 => {
+                        let checkpoint = journal.checkpoint();
+                        let uncompacted_checkpoint = uncompacted.checkpoint();
+                        checkpoints.push((
+                            checkpoint,
+                            uncompacted_checkpoint,
+                            values.clone(),
+                            warm.clone(),
+                        ));
+                    }
+                    1 if !checkpoints.is_empty() => {
+                        checkpoints.pop();
+                        journal.checkpoint_commit();
+                        uncompacted.checkpoint_commit();
+                    }
+                    2 if !checkpoints.is_empty() => {
+                        let (checkpoint, uncompacted_checkpoint, old_values, old_warm) =
+                            checkpoints.pop().unwrap();
+                        journal.checkpoint_revert(checkpoint);
+                        uncompacted.checkpoint_revert(uncompacted_checkpoint);
+                        values = old_values;
+                        warm = old_warm;
+                    }
+                    _ => {
+                        let key = U256::from(next(4));
+                        let value = U256::from(next(4));
+                        // original, present and new value and coldness decide the gas cost.
+                        let result = journal.sstore(a, key, value, &mut db).unwrap();
+                        assert_eq!(
+                            result,
+                            sstore_uncompacted(&mut uncompacted, key, value, &mut db)
+                        );
+                        let (_, present, _, is_cold) = result;
+                        assert_eq!(present, values[&key]);
+                        assert_eq!(is_cold, warm.insert(key));
+                        values.insert(key, value);
+                    }
+                }
+            }
+            while let Some((checkpoint, uncompacted_checkpoint, old_values, old_warm)) =
+                checkpoints.pop()
+            {
+                if next(2) == 0 {
+                    journal.checkpoint_commit();
+                    uncompacted.checkpoint_commit();
+                } else {
+                    journal.checkpoint_revert(checkpoint);
+                    uncompacted.checkpoint_revert(uncompacted_checkpoint);
+                    values = old_values;
+                    warm = old_warm;
+                }
+            }
+
+            assert_eq!(journal.journaled_slots.len(), 1);
+            assert!(journal.journal[0].len() <= uncompacted.journal[0].len());
+            assert_eq!(journal.state, uncompacted.state);
+            for (key, value) in values {
+                let is_cold = !warm.contains(&key);
+                assert_eq!(journal.sload(a, key, &mut db).unwrap(), (value, is_cold));
+            }
+        }
+    }
+
+    #[test]
+    fn committed_checkpoint_is_reverted_with_parent() {
+        let (a, b) = (B160::from(0x1000), B160::from(0x2000));
+        let mut db = db_with_balance(a, 100);
+        let mut journal = JournaledState::new_with_spec(SpecId::LATEST, 0);
+        journal.initial_account_load(a, &[], &mut db).unwrap();
+        journal.initial_account_load(b, &[], &mut db).unwrap();
+
+        let outer = journal.checkpoint();
+        journal
+            .sstore(a, U256::from(1), U256::from(5), &mut db)
+            .unwrap();
+
+        journal.checkpoint();
+        journal.transfer(&a, &b, U256::from(40), &mut db).unwrap();
+        journal.inc_nonce(a);
+        journal.selfdestruct(b, a, &mut db).unwrap();
+        journal.checkpoint_commit();
+
+        let account = journal.account(a);
+        assert_eq!(account.info.balance, U256::from(100));
+        assert_eq!(account.info.nonce, 1);
+        assert!(journal.account(b).is_selfdestructed());
+        assert_eq!(journal.depth(), 1);
+
+        journal.checkpoint_revert(outer);
+        let account = journal.account(a);
+        assert_eq!(account.info.balance, U256::from(100));
+        assert_eq!(account.info.nonce, 0);
+        assert!(!account.is_touched());
+        assert_eq!(account.storage[&U256::from(1)].present_value, U256::ZERO);
+        assert!(!journal.account(b).is_selfdestructed());
+        assert_eq!(journal.account(b).info.balance, U256::ZERO);
+        assert!(journal.selfdestructs.is_empty());
+        assert_eq!(journal.depth(), 0);
+    }
+
+    #[test]
+    fn new_with_spec() {
+        assert!(JournaledState::new_with_spec(SpecId::HOMESTEAD, 4).is_before_spurious_dragon);
+        assert!(
+            !JournaledState::new_with_spec(SpecId::SPURIOUS_DRAGON, 4).is_before_spurious_dragon
+        );
+    }
 
     #[test]
     fn test_is_precompile() {
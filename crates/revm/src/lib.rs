@@ -5,6 +5,7 @@ mod evm;
 mod evm_impl;
 mod inspector;
 mod journaled_state;
+mod spec_info;
 
 #[cfg(all(feature = "with-serde", not(feature = "serde")))]
 compile_error!("`with-serde` feature has been renamed to `serde`.");
@@ -14,8 +15,9 @@ pub type DummyStateDB = InMemoryDB;
 
 pub use db::{Database, DatabaseCommit, InMemoryDB};
 pub use evm::{evm_inner, new, EVM};
-pub use evm_impl::EVMData;
-pub use journaled_state::{JournalEntry, JournaledState};
+pub use evm_impl::{EVMData, VerifiedTx};
+pub use journaled_state::{JournalCheckpoint, JournalEntry, JournaledState};
+pub use spec_info::SpecInfo;
 
 extern crate alloc;
 
@@ -0,0 +1,136 @@
+use crate::evm::to_precompile_id;
+use crate::precompile::Precompiles;
+use crate::primitives::{CfgEnv, GasSchedule, SpecId, B160};
+use alloc::vec::Vec;
+use revm_interpreter::primitives::SpecId::*;
+
+/// Rules that the EVM applies for a configuration.
+///
+/// Values are derived from the same spec checks, configuration methods and precompile sets
+/// that are used during execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpecInfo {
+    pub spec_id: SpecId,
+    /// EIP-161: touched empty accounts are removed from the state.
+    pub state_clear: bool,
+    /// EIP-170: maximum size of the created contract code, `None` if there is no limit.
+    pub max_code_size: Option<usize>,
+    /// EIP-2929: first (cold) access of an account or a slot costs more than later ones.
+    pub warm_cold_access: bool,
+    /// EIP-1559: base fee is burned and the priority fee is paid to the coinbase.
+    pub eip1559: bool,
+    /// EIP-3529: refund of SELFDESTRUCT is removed and refunds are capped to a fifth of gas used.
+    pub reduced_refunds: bool,
+    /// EIP-3541: created code starting with `0xEF` is rejected.
+    pub reject_ef_code: bool,
+    /// EIP-3651: coinbase is warm at the start of the transaction.
+    pub warm_coinbase: bool,
+    /// EIP-3855: PUSH0 instruction.
+    pub push0: bool,
+    /// EIP-3860: maximum size of initcode, `None` if there is no limit.
+    pub max_initcode_size: Option<usize>,
+    /// EIP-5656: MCOPY instruction.
+    pub mcopy: bool,
+    /// Addresses of the precompiles, sorted.
+    pub precompiles: Vec<B160>,
+    /// Gas schedule that is used.
+    pub gas_schedule: GasSchedule,
+}
+
+impl SpecInfo {
+    /// Returns rules of the spec with default configuration.
+    pub fn for_spec(spec_id: SpecId) -> Self {
+        Self::for_cfg(&CfgEnv {
+            spec_id,
+            ..Default::default()
+        })
+    }
+
+    /// Returns rules of the configuration, with its EIP overrides, gas schedule and code size
+    /// limits applied.
+    pub fn for_cfg(cfg: &CfgEnv) -> Self {
+        let spec_id = cfg.spec_id;
+        let enabled = |fork| SpecId::enabled(spec_id, fork);
+        let limit = |size| Some(size).filter(|size| *size != usize::MAX);
+
+        let mut precompiles: Vec<B160> = Precompiles::new(to_precompile_id(spec_id))
+            .addresses()
+            .into_iter()
+            .copied()
+            .collect();
+        precompiles.sort();
+
+        Self {
+            spec_id,
+            state_clear: enabled(SPURIOUS_DRAGON),
+            max_code_size: enabled(SPURIOUS_DRAGON)
+                .then(|| limit(cfg.max_code_size()))
+                .flatten(),
+            warm_cold_access: enabled(BERLIN),
+            eip1559: enabled(LONDON),
+            reduced_refunds: enabled(LONDON),
+            reject_ef_code: enabled(LONDON),
+            warm_coinbase: enabled(SHANGHAI),
+            push0: cfg.eip_overrides.is_push0_enabled(spec_id),
+            max_initcode_size: enabled(SHANGHAI)
+                .then(|| limit(cfg.max_initcode_size()))
+                .flatten(),
+            mcopy: enabled(CANCUN),
+            precompiles,
+            gas_schedule: cfg.gas_schedule(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{EipOverrides, MAX_CODE_SIZE, MAX_INITCODE_SIZE};
+
+    #[test]
+    fn known_specs() {
+        let frontier = SpecInfo::for_spec(FRONTIER);
+        assert!(!frontier.state_clear);
+        assert_eq!(frontier.max_code_size, None);
+        assert_eq!(
+            frontier.precompiles,
+            (1..=4u64).map(B160::from).collect::<Vec<_>>()
+        );
+        assert_eq!(frontier.gas_schedule.max_refund_quotient, 2);
+
+        let berlin = SpecInfo::for_spec(BERLIN);
+        assert!(berlin.warm_cold_access);
+        assert!(!berlin.reduced_refunds);
+        assert_eq!(berlin.precompiles.len(), 9);
+
+        let london = SpecInfo::for_spec(LONDON);
+        assert!(london.eip1559 && london.reduced_refunds);
+        assert_eq!(london.gas_schedule.max_refund_quotient, 5);
+        assert!(!london.push0);
+        assert_eq!(london.max_initcode_size, None);
+
+        let shanghai = SpecInfo::for_spec(SHANGHAI);
+        assert!(shanghai.push0 && shanghai.warm_coinbase);
+        assert_eq!(shanghai.max_code_size, Some(MAX_CODE_SIZE));
+        assert_eq!(shanghai.max_initcode_size, Some(MAX_INITCODE_SIZE));
+        assert!(!shanghai.mcopy);
+        assert!(SpecInfo::for_spec(CANCUN).mcopy);
+    }
+
+    #[test]
+    fn configuration_is_applied() {
+        let cfg = CfgEnv {
+            spec_id: LONDON,
+            limit_contract_code_size: Some(0),
+            eip_overrides: EipOverrides {
+                eip3855_push0: Some(true),
+            },
+            ..Default::default()
+        };
+        let info = SpecInfo::for_cfg(&cfg);
+        assert!(info.push0);
+        assert_eq!(info.max_code_size, None);
+        assert_eq!(info.gas_schedule, GasSchedule::new(LONDON));
+    }
+}
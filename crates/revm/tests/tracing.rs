@@ -0,0 +1,98 @@
+#![cfg(feature = "tracing")]
+
+use revm::primitives::{hex_literal::hex, AccountInfo, Bytecode, Bytes, TransactTo, B160, U256};
+use revm::InMemoryDB;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[test]
+fn tracing_spans() {
+    type Spans = Arc<Mutex<Vec<(&'static str, Vec<(&'static str, String)>)>>>;
+
+    /// Records names and fields of the created spans.
+    struct SpanRecorder(Spans);
+
+    struct FieldVisitor<'a>(&'a mut Vec<(&'static str, String)>);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+            self.0.push((field.name(), format!("{value:?}")));
+        }
+    }
+
+    impl Subscriber for SpanRecorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let mut spans = self.0.lock().unwrap();
+            let mut fields = Vec::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            spans.push((attrs.metadata().name(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.0.lock().unwrap();
+            let index = span.into_u64() as usize - 1;
+            values.record(&mut FieldVisitor(&mut spans[index].1));
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    // STATICCALL to the identity precompile:
+    // PUSH1 0, PUSH1 0, PUSH1 0, PUSH1 0, PUSH1 4, GAS, STATICCALL, STOP
+    let contract = B160::from(0x1000);
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        contract,
+        AccountInfo::new(
+            U256::ZERO,
+            1,
+            Bytecode::new_raw(Bytes::from_static(&hex!("600060006000600060045afa00"))),
+        ),
+    );
+    let mut evm = revm::new();
+    evm.database(db);
+    evm.env.tx.transact_to = TransactTo::Call(contract);
+    evm.env.tx.gas_limit = 100_000;
+
+    let spans = Spans::default();
+    let result =
+        tracing::subscriber::with_default(SpanRecorder(spans.clone()), || evm.transact().unwrap());
+
+    let spans = spans.lock().unwrap();
+    let names: Vec<_> = spans.iter().map(|(name, _)| *name).collect();
+    assert_eq!(
+        names,
+        ["preverify", "transact", "interpreter", "precompile"]
+    );
+
+    let field = |span: usize, name: &str| {
+        spans[span]
+            .1
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.clone())
+    };
+    assert_eq!(field(1, "nonce"), Some("0".into()));
+    assert_eq!(field(1, "gas_limit"), Some("100000".into()));
+    assert_eq!(
+        field(1, "gas_used"),
+        Some(result.result.gas_used().to_string())
+    );
+    assert_eq!(field(1, "accounts"), Some(result.state.len().to_string()));
+    assert!(field(2, "gas_used").is_some());
+    // identity costs 15 gas for an empty input.
+    assert_eq!(field(3, "gas_used"), Some("15".into()));
+}
@@ -0,0 +1,99 @@
+//! Forces the value returned by an oracle by patching its STATICCALL output from an inspector.
+
+use revm::{
+    db::InMemoryDB,
+    interpreter::{opcode, InstructionResult, Interpreter},
+    primitives::{hex_literal::hex, AccountInfo, Bytecode, Bytes, TransactTo, B160, U256},
+    Database, EVMData, Inspector,
+};
+
+/// Overrides the first word returned by STATICCALLs to `oracle`.
+struct OraclePatch {
+    oracle: B160,
+    price: U256,
+    /// Memory offset of the output of the patched call that is running.
+    ret_offset: Option<usize>,
+}
+
+impl<DB: Database> Inspector<DB> for OraclePatch {
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EVMData<'_, DB>) -> InstructionResult {
+        if interp.current_opcode() != opcode::STATICCALL {
+            return InstructionResult::Continue;
+        }
+        // STATICCALL has not run yet, arguments are still on the stack.
+        if let [ret_size, ret_offset, _, _, address, _] = *interp.stack.peek_n(6) {
+            let address = B160::from_slice(&address.to_be_bytes::<32>()[12..]);
+            if address == self.oracle && ret_size >= U256::from(32) {
+                self.ret_offset = usize::try_from(ret_offset).ok();
+            }
+        }
+        InstructionResult::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DB>,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        let Some(ret_offset) = self.ret_offset.take() else {
+            return eval;
+        };
+        // the call is done and its success flag is on top of the stack.
+        if interp.stack.peek(0) == Ok(U256::from(1)) && interp.return_data_buffer.len() >= 32 {
+            let price = self.price.to_be_bytes::<32>();
+            interp.memory.set(ret_offset, &price);
+            let mut return_data = interp.return_data_buffer.to_vec();
+            return_data[..32].copy_from_slice(&price);
+            interp.return_data_buffer = return_data.into();
+        }
+        eval
+    }
+}
+
+fn main() {
+    let oracle = B160::from(0x1000);
+    let consumer = B160::from(0x2000);
+
+    let mut db = InMemoryDB::default();
+    // returns 100: PUSH1 100, PUSH1 0, MSTORE, PUSH1 32, PUSH1 0, RETURN
+    db.insert_account_info(
+        oracle,
+        AccountInfo::new(
+            U256::ZERO,
+            1,
+            Bytecode::new_raw(Bytes::from_static(&hex!("606460005260206000f3"))),
+        ),
+    );
+    // STATICCALL(gas, oracle, 0, 0, 0, 32), then stores the price to slot 0.
+    // PUSH1 32, PUSH1 0, PUSH1 0, PUSH1 0, PUSH2 0x1000, GAS, STATICCALL, POP,
+    // PUSH1 0, MLOAD, PUSH1 0, SSTORE
+    db.insert_account_info(
+        consumer,
+        AccountInfo::new(
+            U256::ZERO,
+            1,
+            Bytecode::new_raw(Bytes::from_static(&hex!(
+                "60206000600060006110005afa50600051600055"
+            ))),
+        ),
+    );
+
+    let mut evm = revm::new();
+    evm.database(db);
+    evm.env.tx.transact_to = TransactTo::Call(consumer);
+    evm.env.tx.gas_limit = 1_000_000;
+
+    let price = evm.transact().unwrap().state[&consumer].storage[&U256::ZERO].present_value;
+    assert_eq!(price, U256::from(100));
+
+    let patch = OraclePatch {
+        oracle,
+        price: U256::from(42),
+        ret_offset: None,
+    };
+    let state = evm.inspect(patch).unwrap().state;
+    let price = state[&consumer].storage[&U256::ZERO].present_value;
+    assert_eq!(price, U256::from(42));
+    println!("patched oracle price: {price}");
+}